@@ -0,0 +1,289 @@
+// Faulhaber's formula for power sums:
+//
+//   1^k + 2^k + ... + n^k = 1/(k+1) * sum_{j=0}^{k} C(k+1, j) * B_j * n^(k+1-j)
+//
+// where B_j are the Bernoulli numbers with the B_1 = +1/2 convention.
+//
+// Everything is kept as exact `Rational`s; with the default i128 backend any
+// step that would overflow yields `None` rather than a wrong answer.
+
+use crate::rational::{Rational, RationalInt};
+
+// Row k of Pascal's triangle: C(k, 0), ..., C(k, k)
+fn binomial_row<T: RationalInt>(k: usize) -> Option<Vec<T>> {
+    let mut row = vec![T::one()];
+    for _ in 0..k {
+        let mut next = vec![T::one(); row.len() + 1];
+        for j in 1..row.len() {
+            next[j] = row[j - 1].checked_add(&row[j])?;
+        }
+        row = next;
+    }
+    Some(row)
+}
+
+// B_0..=B_k from the recurrence sum_{j=0}^{m} C(m+1, j) B_j = 0,
+// which yields B_1 = -1/2; the sign is flipped afterwards.
+fn bernoulli_upto<T: RationalInt>(k: usize) -> Option<Vec<Rational<T>>> {
+    let mut b = Vec::with_capacity(k + 1);
+    b.push(Rational::one());
+
+    for m in 1..=k {
+        let row = binomial_row::<T>(m + 1)?;
+        let mut acc = Rational::zero();
+        for (bj, c) in b.iter().zip(row) {
+            acc = acc.checked_add(&bj.checked_mul(&Rational::from_integer(c))?)?;
+        }
+        // B_m = -acc / (m + 1)
+        let scale = Rational::new(T::from_i128(-1), T::from_i128(m as i128 + 1))?;
+        b.push(acc.checked_mul(&scale)?);
+    }
+
+    if k >= 1 {
+        b[1] = Rational::new(T::one(), T::from_i128(2))?;
+    }
+    Some(b)
+}
+
+// Bernoulli number B_k, with B_1 = +1/2.
+pub fn bernoulli(k: usize) -> Option<Rational> {
+    bernoulli_upto(k)?.pop()
+}
+
+fn power_sum_exact<T: RationalInt>(n: u64, k: usize) -> Option<Rational<T>> {
+    let b = bernoulli_upto::<T>(k)?;
+    let row = binomial_row::<T>(k + 1)?;
+    let x = Rational::from_integer(T::from_i128(n as i128));
+
+    // Horner over the polynomial in n, highest power (j = 0) first;
+    // the trailing multiplication by n accounts for the missing constant term.
+    let mut acc = Rational::zero();
+    for (bj, c) in b.iter().zip(row) {
+        let coeff = bj.checked_mul(&Rational::from_integer(c))?;
+        acc = acc.checked_mul(&x)?.checked_add(&coeff)?;
+    }
+    let scale = Rational::new(T::one(), T::from_i128(k as i128 + 1))?;
+    let total = acc.checked_mul(&x)?.checked_mul(&scale)?;
+
+    // Invariant: a sum of non-negative integers is a non-negative integer
+    debug_assert!(total.is_integer());
+    Some(total)
+}
+
+// Closed forms for k <= 3. The denominators are divided out of the factor
+// they divide before multiplying, so only the result itself has to fit.
+fn power_sum_small(n: u64, k: u32) -> Option<u128> {
+    let n = n as u128;
+    // n(n+1)/2, halving whichever factor is even; at most 2^127
+    let triangle = if n % 2 == 0 { (n / 2) * (n + 1) } else { n * n.div_ceil(2) };
+    match k {
+        0 => Some(n),
+        1 => Some(triangle),
+        2 => {
+            // Invariant: 3 divides one of n, n+1 and 2n+1, and if it is not
+            // 2n+1 it divides n(n+1)/2 since 3 is coprime to 2
+            let odd = 2 * n + 1;
+            if odd % 3 == 0 {
+                triangle.checked_mul(odd / 3)
+            } else {
+                (triangle / 3).checked_mul(odd)
+            }
+        }
+        3 => triangle.checked_mul(triangle),
+        _ => unreachable!("no closed form for k = {k}"),
+    }
+}
+
+// Exact 1^k + 2^k + ... + n^k, or `None` if it does not fit.
+//
+// For k <= 3 `None` means the sum itself exceeds u128. For larger k the
+// general formula runs in i128 fractions, so `None` can also mean an
+// intermediate step overflowed even though the sum would fit; use
+// `power_sum_big` for those.
+pub fn power_sum(n: u64, k: u32) -> Option<u128> {
+    if k <= 3 {
+        return power_sum_small(n, k);
+    }
+    let total = power_sum_exact::<i128>(n, k as usize)?;
+    u128::try_from(total.to_integer()?).ok()
+}
+
+// Exact 1^k + 2^k + ... + n^k for any n and k.
+#[cfg(feature = "bigint")]
+pub fn power_sum_big(n: u64, k: u32) -> num_bigint::BigInt {
+    power_sum_exact(n, k as usize)
+        .and_then(|total| total.to_integer())
+        .expect("big-integer power sums never overflow")
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+// In u128, since a + b can pass u64::MAX once m > 2^63
+fn add_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 + b as u128) % m as u128) as u64
+}
+
+// (1^k + 2^k + ... + n^k) mod m, for any modulus m >= 1.
+//
+// Bernoulli denominators are not invertible modulo an arbitrary m, so this
+// instead expands i^k over falling factorials with Stirling numbers of the
+// second kind:
+//
+//   sum_{i=1}^{n} i^k = sum_{j=1}^{k} S(k, j) * j! * C(n+1, j+1)
+//
+// and j! * C(n+1, j+1) = (n+1) n ... (n+1-j) / (j+1). One of those j+1
+// consecutive factors is divisible by j+1, so the division is done exactly
+// on that factor before reducing mod m. Cost is O(k^2), independent of n.
+pub fn power_sum_mod(n: u64, k: u32, m: u64) -> u64 {
+    assert!(m > 0, "modulus must be >= 1");
+    if m == 1 {
+        return 0;
+    }
+    if k == 0 {
+        return n % m;
+    }
+
+    let k = k as usize;
+
+    // Row k of the Stirling numbers of the second kind, mod m
+    let mut stirling = vec![0_u64; k + 1];
+    stirling[0] = 1;
+    for i in 1..=k {
+        for j in (1..=i).rev() {
+            stirling[j] = add_mod(mul_mod(j as u64, stirling[j], m), stirling[j - 1], m);
+        }
+        stirling[0] = 0;
+    }
+
+    let top = n as u128 + 1;
+    let mut total = 0_u64;
+    for (j, &s) in stirling.iter().enumerate().skip(1) {
+        // C(n+1, j+1) = 0 once j+1 > n+1
+        if j as u128 > n as u128 || s == 0 {
+            continue;
+        }
+        let divisor = j as u128 + 1;
+        let low = top - j as u128;
+
+        // Invariant: j+1 consecutive integers contain a multiple of j+1
+        let special = low + (divisor - low % divisor) % divisor;
+        debug_assert!(special <= top);
+
+        let falling = (low..=top).fold(1_u64, |acc, f| {
+            let f = if f == special { f / divisor } else { f };
+            mul_mod(acc, (f % m as u128) as u64, m)
+        });
+        total = add_mod(total, mul_mod(s, falling, m), m);
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute(n: u64, k: u32) -> u128 {
+        (1..=n as u128).map(|i| i.pow(k)).sum()
+    }
+
+    #[test]
+    fn bernoulli_known_values() {
+        let b = |k| bernoulli(k).unwrap().to_string();
+        assert_eq!(b(0), "1");
+        assert_eq!(b(1), "1/2");
+        assert_eq!(b(2), "1/6");
+        assert_eq!(b(3), "0");
+        assert_eq!(b(4), "-1/30");
+        assert_eq!(b(12), "-691/2730");
+    }
+
+    #[test]
+    fn power_sum_matches_brute_force() {
+        for k in 0..=8 {
+            for n in 0..=200 {
+                assert_eq!(power_sum(n, k), Some(brute(n, k)), "n = {n}, k = {k}");
+            }
+        }
+    }
+
+    #[test]
+    fn power_sum_reports_overflow() {
+        assert_eq!(power_sum(u64::MAX, 3), None);
+        assert_eq!(power_sum(u64::MAX, 2), None);
+    }
+
+    #[test]
+    fn small_powers_reach_the_u128_limit() {
+        let n = u64::MAX as u128;
+        assert_eq!(power_sum(u64::MAX, 0), Some(n));
+        assert_eq!(power_sum(u64::MAX, 1), Some(n << 63));
+        // The largest sums of squares and cubes that still fit
+        for (n, k) in [(10_069_012_961_344, 2), (10_069_012_961_343, 2), (6_074_000_999, 3)] {
+            let total = power_sum(n, k).unwrap();
+            for m in [1_000_000_007, 998_244_353, 1 << 61] {
+                assert_eq!((total % m as u128) as u64, power_sum_mod(n, k, m), "n = {n}, k = {k}");
+            }
+        }
+        assert_eq!(power_sum(10_069_012_961_345, 2), None);
+        assert_eq!(power_sum(6_074_001_000, 3), None);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn power_sum_big_matches_mod() {
+        let n: u64 = 1_000_000_000_000_000_000;
+        let m = num_bigint::BigInt::from(1_000_000_007_u64);
+        for k in [3, 10, 25] {
+            let exact = power_sum_big(n, k) % &m;
+            assert_eq!(exact, power_sum_mod(n, k, 1_000_000_007).into());
+        }
+        // Residues this close to u64::MAX overflow a u64 addition
+        let (n, m) = (999_999_999_999_999_999_u64, u64::MAX - 58);
+        for k in 1..=7 {
+            let exact = power_sum_big(n, k) % num_bigint::BigInt::from(m);
+            assert_eq!(exact, power_sum_mod(n, k, m).into(), "k = {k}");
+        }
+    }
+
+    #[test]
+    fn power_sum_mod_matches_exact() {
+        for &m in &[2_u64, 6, 97, 1_000_000_007, 1 << 40] {
+            for k in 0..=8 {
+                for n in 0..=60 {
+                    let exact = power_sum(n, k).unwrap();
+                    assert_eq!(
+                        power_sum_mod(n, k, m) as u128,
+                        exact % m as u128,
+                        "n = {n}, k = {k}, m = {m}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn power_sum_mod_handles_huge_n() {
+        let n: u64 = 1_000_000_000_000_000_000;
+        let m: u64 = 1_000_000_007;
+        let triangle = n as u128 * (n as u128 + 1) / 2;
+        assert_eq!(power_sum_mod(n, 1, m) as u128, triangle % m as u128);
+        assert_eq!(power_sum_mod(n, 0, m), n % m);
+    }
+
+    #[test]
+    fn power_sum_mod_handles_moduli_near_u64_max() {
+        let (n, m) = (999_999_999_999_999_999_u64, u64::MAX - 58);
+        let triangle = n as u128 * (n as u128 + 1) / 2;
+        assert_eq!(power_sum_mod(n, 1, m) as u128, triangle % m as u128);
+        assert_eq!(power_sum_mod(n, 2, m), 10_869_442_434_218_958_979);
+        for m in [u64::MAX, u64::MAX - 58, (1 << 63) + 1] {
+            for n in [0, 1, 2, 1_000, 999_999_999_999_999_999] {
+                for k in 0..=7 {
+                    assert!(power_sum_mod(n, k, m) < m, "n = {n}, k = {k}, m = {m}");
+                }
+            }
+        }
+    }
+}
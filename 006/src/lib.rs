@@ -0,0 +1,54 @@
+mod faulhaber;
+pub mod rational;
+
+pub use faulhaber::{bernoulli, power_sum, power_sum_mod};
+#[cfg(feature = "bigint")]
+pub use faulhaber::power_sum_big;
+pub use rational::Rational;
+
+pub fn sum_of_squares(num: u64) -> u64 {
+    (1..=num)
+        .map(|i| i*i)
+        .sum()
+}
+
+pub fn square_of_sum(num: u64) -> u64 {
+    let r: u64 = (1..=num).sum();
+    r * r
+}
+
+// (n(n+1)/2)^2 - n(n+1)(2n+1)/6 = n(n+1)(n-1)(3n+2)/12
+//
+// Computed from the power sums so no term is looped over; returns `None`
+// once the square of the sum no longer fits in a u128.
+pub fn difference(num: u64) -> Option<u128> {
+    let sum = power_sum(num, 1)?;
+    let squares = power_sum(num, 2)?;
+    sum.checked_mul(sum)?.checked_sub(squares)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn difference_matches_problem_statement() {
+        assert_eq!(difference(10), Some(2640));
+        assert_eq!(difference(100), Some(25_164_150));
+    }
+
+    #[test]
+    fn difference_matches_iterators() {
+        for n in 0..=1_000 {
+            let expected = (square_of_sum(n) - sum_of_squares(n)) as u128;
+            assert_eq!(difference(n), Some(expected), "n = {n}");
+        }
+    }
+
+    #[test]
+    fn difference_handles_large_n() {
+        let n = 1_000_000_000_u128;
+        let expected = n * (n + 1) * (n - 1) * (3 * n + 2) / 12;
+        assert_eq!(difference(1_000_000_000), Some(expected));
+    }
+}
@@ -0,0 +1,439 @@
+// Exact fractions kept in lowest terms:
+//
+//   - den > 0
+//   - gcd(num, den) == 1 (and 0 is stored as 0/1)
+//
+// With those invariants two equal values always have the same
+// representation, so equality and hashing can be derived structurally.
+//
+// The integer backend is pluggable through `RationalInt`. `i128` is the
+// default and reports overflow through the `checked_*` methods; with the
+// `bigint` feature `num_bigint::BigInt` can be used and never overflows.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+pub trait RationalInt: Clone + Eq + Ord + fmt::Display + FromStr {
+    fn from_i128(v: i128) -> Self;
+    fn to_i128(&self) -> Option<i128>;
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_sub(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+    fn checked_neg(&self) -> Option<Self>;
+    // Euclidean quotient and remainder; callers guarantee rhs > 0
+    fn div_rem_euclid(&self, rhs: &Self) -> (Self, Self);
+    // Non-negative gcd, or `None` if it is not representable
+    fn gcd(&self, rhs: &Self) -> Option<Self>;
+
+    fn zero() -> Self {
+        Self::from_i128(0)
+    }
+
+    fn one() -> Self {
+        Self::from_i128(1)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+
+    fn is_negative(&self) -> bool {
+        *self < Self::zero()
+    }
+}
+
+impl RationalInt for i128 {
+    fn from_i128(v: i128) -> Self {
+        v
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        Some(*self)
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        i128::checked_add(*self, *rhs)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        i128::checked_sub(*self, *rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        i128::checked_mul(*self, *rhs)
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        i128::checked_neg(*self)
+    }
+
+    fn div_rem_euclid(&self, rhs: &Self) -> (Self, Self) {
+        (self.div_euclid(*rhs), self.rem_euclid(*rhs))
+    }
+
+    fn gcd(&self, rhs: &Self) -> Option<Self> {
+        // Work in u128 so i128::MIN does not overflow on abs()
+        let (mut a, mut b) = (self.unsigned_abs(), rhs.unsigned_abs());
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        i128::try_from(a).ok()
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl RationalInt for num_bigint::BigInt {
+    fn from_i128(v: i128) -> Self {
+        Self::from(v)
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        i128::try_from(self).ok()
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        Some(self - rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+
+    fn checked_neg(&self) -> Option<Self> {
+        Some(-self)
+    }
+
+    fn div_rem_euclid(&self, rhs: &Self) -> (Self, Self) {
+        let (q, r) = (self / rhs, self % rhs);
+        if r.is_negative() {
+            (q - 1, r + rhs)
+        } else {
+            (q, r)
+        }
+    }
+
+    fn gcd(&self, rhs: &Self) -> Option<Self> {
+        let (mut a, mut b) = (self.magnitude().clone(), rhs.magnitude().clone());
+        while b != num_bigint::BigUint::ZERO {
+            let r = &a % &b;
+            (a, b) = (b, r);
+        }
+        Some(a.into())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rational<T = i128> {
+    num: T,
+    den: T,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseRationalError {
+    BadNumber,
+    ZeroDenominator,
+    Overflow,
+}
+
+impl fmt::Display for ParseRationalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRationalError::BadNumber => write!(f, "invalid number"),
+            ParseRationalError::ZeroDenominator => write!(f, "zero denominator"),
+            ParseRationalError::Overflow => write!(f, "value out of range"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRationalError {}
+
+// Callers guarantee d > 0 and d divides n
+fn exact_div<T: RationalInt>(n: &T, d: &T) -> T {
+    n.div_rem_euclid(d).0
+}
+
+impl<T: RationalInt> Rational<T> {
+    // `None` on a zero denominator or if normalizing overflows
+    pub fn new(num: T, den: T) -> Option<Self> {
+        if den.is_zero() {
+            return None;
+        }
+        let Some(g) = num.gcd(&den) else {
+            // Only a gcd of 2^127 does not fit an i128, as for
+            // new(i128::MIN, i128::MIN). Both sides are even then, and
+            // halving them first leaves the value unchanged.
+            let two = T::from_i128(2);
+            return Self::new(exact_div(&num, &two), exact_div(&den, &two));
+        };
+        let (num, den) = (exact_div(&num, &g), exact_div(&den, &g));
+        if den.is_negative() {
+            Some(Self { num: num.checked_neg()?, den: den.checked_neg()? })
+        } else {
+            Some(Self { num, den })
+        }
+    }
+
+    pub fn from_integer(n: T) -> Self {
+        Self { num: n, den: T::one() }
+    }
+
+    pub fn zero() -> Self {
+        Self::from_integer(T::zero())
+    }
+
+    pub fn one() -> Self {
+        Self::from_integer(T::one())
+    }
+
+    pub fn numer(&self) -> &T {
+        &self.num
+    }
+
+    pub fn denom(&self) -> &T {
+        &self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num.is_zero()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == T::one()
+    }
+
+    pub fn to_integer(&self) -> Option<T> {
+        self.is_integer().then(|| self.num.clone())
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Some(Self { num: self.num.checked_neg()?, den: self.den.clone() })
+    }
+
+    pub fn checked_recip(&self) -> Option<Self> {
+        Self::new(self.den.clone(), self.num.clone())
+    }
+
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        // Scale by lcm(b, d) rather than b * d to keep the terms small
+        let g = self.den.gcd(&rhs.den)?;
+        let l = exact_div(&self.den, &g);
+        let r = exact_div(&rhs.den, &g);
+        let num = self.num.checked_mul(&r)?.checked_add(&rhs.num.checked_mul(&l)?)?;
+        let den = self.den.checked_mul(&r)?;
+        Self::new(num, den)
+    }
+
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        self.checked_add(&rhs.checked_neg()?)
+    }
+
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        // Cross-cancel before multiplying; both results are already reduced
+        let g1 = self.num.gcd(&rhs.den)?;
+        let g2 = rhs.num.gcd(&self.den)?;
+        let num = exact_div(&self.num, &g1).checked_mul(&exact_div(&rhs.num, &g2))?;
+        let den = exact_div(&self.den, &g2).checked_mul(&exact_div(&rhs.den, &g1))?;
+        Self::new(num, den)
+    }
+
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        self.checked_mul(&rhs.checked_recip()?)
+    }
+}
+
+impl<T: RationalInt> From<T> for Rational<T> {
+    fn from(n: T) -> Self {
+        Self::from_integer(n)
+    }
+}
+
+impl<T: RationalInt> Add for Rational<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.checked_add(&rhs).expect("attempt to add with overflow")
+    }
+}
+
+impl<T: RationalInt> Sub for Rational<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.checked_sub(&rhs).expect("attempt to subtract with overflow")
+    }
+}
+
+impl<T: RationalInt> Mul for Rational<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.checked_mul(&rhs).expect("attempt to multiply with overflow")
+    }
+}
+
+impl<T: RationalInt> Div for Rational<T> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        assert!(!rhs.is_zero(), "attempt to divide by zero");
+        self.checked_div(&rhs).expect("attempt to divide with overflow")
+    }
+}
+
+impl<T: RationalInt> Neg for Rational<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.checked_neg().expect("attempt to negate with overflow")
+    }
+}
+
+// Compares a/b with c/d by walking their continued fractions, so no
+// cross-multiplication (and no overflow) is needed.
+fn cmp_fractions<T: RationalInt>(a: &T, b: &T, c: &T, d: &T) -> Ordering {
+    let (q1, r1) = a.div_rem_euclid(b);
+    let (q2, r2) = c.div_rem_euclid(d);
+    match q1.cmp(&q2) {
+        Ordering::Equal => {}
+        other => return other,
+    }
+    match (r1.is_zero(), r2.is_zero()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        // r1/b vs r2/d, both in (0, 1), is the reverse of b/r1 vs d/r2
+        (false, false) => cmp_fractions(d, &r2, b, &r1),
+    }
+}
+
+impl<T: RationalInt> Ord for Rational<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_fractions(&self.num, &self.den, &other.num, &other.den)
+    }
+}
+
+impl<T: RationalInt> PartialOrd for Rational<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: RationalInt> fmt::Display for Rational<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+// Accepts "n" or "n/d", with optional surrounding whitespace
+impl<T: RationalInt> FromStr for Rational<T> {
+    type Err = ParseRationalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |t: &str| t.trim().parse::<T>().map_err(|_| ParseRationalError::BadNumber);
+        let (num, den) = match s.split_once('/') {
+            Some((n, d)) => (parse(n)?, parse(d)?),
+            None => (parse(s)?, T::one()),
+        };
+        if den.is_zero() {
+            return Err(ParseRationalError::ZeroDenominator);
+        }
+        Self::new(num, den).ok_or(ParseRationalError::Overflow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(num: i128, den: i128) -> Rational {
+        Rational::new(num, den).unwrap()
+    }
+
+    #[test]
+    fn new_normalizes() {
+        assert_eq!(r(2, 4), r(1, 2));
+        assert_eq!(r(3, -6), r(-1, 2));
+        assert_eq!(r(0, -5), Rational::zero());
+        assert_eq!(*r(-3, -9).denom(), 3);
+        assert_eq!(Rational::<i128>::new(1, 0), None);
+    }
+
+    #[test]
+    fn new_handles_i128_min() {
+        // gcd 2^127 does not fit, but the values do
+        let min = i128::MIN;
+        assert_eq!(Rational::new(min, min), Some(Rational::one()));
+        assert_eq!(Rational::new(0, min), Some(Rational::zero()));
+        assert_eq!(Rational::new(min, 2), Some(r(min / 2, 1)));
+        assert_eq!(Rational::new(min, min / 2), Some(r(2, 1)));
+        assert_eq!(*Rational::new(min, 1).unwrap().numer(), min);
+        // 2^127 itself is out of range
+        assert_eq!(Rational::new(min, -1), None);
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(r(1, 2) + r(1, 3), r(5, 6));
+        assert_eq!(r(1, 2) - r(1, 3), r(1, 6));
+        assert_eq!(r(2, 3) * r(9, 4), r(3, 2));
+        assert_eq!(r(2, 3) / r(4, 9), r(3, 2));
+        assert_eq!(-r(2, 3), r(-2, 3));
+        assert_eq!(r(1, 6) + r(1, 3) + r(1, 2), Rational::one());
+    }
+
+    #[test]
+    fn checked_ops_report_overflow() {
+        let big = Rational::from_integer(i128::MAX);
+        assert_eq!(big.checked_add(&Rational::one()), None);
+        assert_eq!(big.checked_mul(&r(2, 1)), None);
+        assert_eq!(Rational::from_integer(i128::MIN).checked_neg(), None);
+        assert_eq!(r(1, 2).checked_div(&Rational::zero()), None);
+        // Cross-cancellation keeps this in range
+        assert_eq!(big.checked_mul(&r(1, i128::MAX)), Some(Rational::one()));
+    }
+
+    #[test]
+    fn ordering_does_not_overflow() {
+        assert!(r(1, 3) < r(1, 2));
+        assert!(r(-1, 2) < r(-1, 3));
+        assert!(r(-1, 2) < r(0, 1));
+        assert_eq!(r(2, 4).cmp(&r(1, 2)), Ordering::Equal);
+
+        let a = r(i128::MAX - 1, i128::MAX);
+        let b = r(i128::MAX - 2, i128::MAX - 1);
+        assert!(b < a);
+    }
+
+    #[test]
+    fn parse_and_display_round_trip() {
+        for s in ["0", "7", "-7", "1/2", "-691/2730"] {
+            assert_eq!(s.parse::<Rational>().unwrap().to_string(), s);
+        }
+        assert_eq!(" 6 / -4 ".parse::<Rational>(), Ok(r(-3, 2)));
+        assert_eq!("1/0".parse::<Rational>(), Err(ParseRationalError::ZeroDenominator));
+        assert_eq!("x/2".parse::<Rational>(), Err(ParseRationalError::BadNumber));
+        assert_eq!("1/2/3".parse::<Rational>(), Err(ParseRationalError::BadNumber));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn bigint_backend_does_not_overflow() {
+        use num_bigint::BigInt;
+
+        let big = Rational::<BigInt>::from_integer(BigInt::from(i128::MAX));
+        let sum = big.clone() + big.clone();
+        assert_eq!(*sum.numer(), BigInt::from(i128::MAX) * 2);
+        assert_eq!(sum / big, Rational::from_integer(BigInt::from(2)));
+        assert_eq!("-4/6".parse::<Rational<BigInt>>().unwrap().to_string(), "-2/3");
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const N: usize = 10001;
+
+fn bench_nth_prime(c: &mut Criterion) {
+    c.bench_function("nth_prime", |b| {
+        b.iter(|| euler007::nth_prime(black_box(N)))
+    });
+}
+
+fn bench_nth_prime_sieve(c: &mut Criterion) {
+    c.bench_function("nth_prime_sieve", |b| {
+        b.iter(|| euler007::nth_prime_sieve(black_box(N)))
+    });
+}
+
+fn bench_segmented_primes(c: &mut Criterion) {
+    c.bench_function("segmented_primes_nth", |b| {
+        b.iter(|| euler007::SegmentedPrimes::new().nth(black_box(N - 1)))
+    });
+}
+
+fn bench_heap_primes(c: &mut Criterion) {
+    c.bench_function("heap_primes_nth", |b| {
+        b.iter(|| euler007::HeapPrimes::new().nth(black_box(N - 1)))
+    });
+}
+
+fn criterion_config() -> Criterion {
+    Criterion::default()
+        .sample_size(50)
+        .warm_up_time(Duration::from_secs(2))
+        .measurement_time(Duration::from_secs(10))
+}
+
+criterion_group!(
+    name = primes;
+    config = criterion_config();
+    targets = bench_nth_prime, bench_nth_prime_sieve, bench_segmented_primes, bench_heap_primes
+);
+criterion_main!(primes);
@@ -0,0 +1,46 @@
+mod prime_pi;
+mod primes;
+mod sieve;
+
+pub use prime_pi::{nth_prime_pi, prime_pi};
+pub use primes::{primes, primes_in, HeapPrimes, SegmentedPrimes};
+pub use sieve::{nth_prime_sieve, nth_prime_upper_bound};
+
+pub fn is_prime(num: u64, primes: &[u64]) -> bool {
+
+    if num < 2 {
+        return false;
+    }
+
+    for &p in primes {
+        if p * p > num {
+            break;
+        }
+        if num % p == 0 {
+            return false;
+        }
+    }
+    true
+}
+
+pub fn nth_prime(n: usize) -> u64 {
+    if n == 0 { 
+        panic!("n must be >= 1");
+    }
+
+    if n == 1 { 
+        return 2;
+    }
+
+    let mut primes: Vec<u64> = vec![2];
+    let mut candidate: u64 = 3;
+
+    while primes.len() < n {
+        if is_prime(candidate, &primes) {
+            primes.push(candidate);
+        }
+        candidate += 2;
+    }
+
+    *primes.last().unwrap()
+}
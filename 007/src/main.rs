@@ -1,44 +1,16 @@
-
-fn is_prime(num: u64, primes: &[u64]) -> bool {
-
-    if num < 2 {
-        return false;
-    }
-
-    for &p in primes {
-        if p * p > num {
-            break;
-        }
-        if num % p == 0 {
-            return false;
-        }
-    }
-    true
-}
-
-fn nth_prime(n: usize) -> u64 {
-    if n == 0 { 
-        panic!("n must be >= 1");
-    }
-
-    if n == 1 { 
-        return 2;
-    }
-
-    let mut primes: Vec<u64> = vec![2];
-    let mut candidate: u64 = 3;
-
-    while primes.len() < n {
-        if is_prime(candidate, &primes) {
-            primes.push(candidate);
-        }
-        candidate += 2;
+use std::env;
+use std::error::Error;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Usage: euler007 [n]
+    let n: usize = match env::args().nth(1) {
+        Some(arg) => arg.parse().map_err(|e| format!("Invalid n {arg:?}: {e}"))?,
+        None => 10001,
+    };
+    if n == 0 {
+        return Err("n must be >= 1".into());
     }
-
-    *primes.last().unwrap()
-}
-
-fn main() {
-    let result = nth_prime(10001);
+    let result = euler007::nth_prime_sieve(n);
     println!("{result}");
-}
\ No newline at end of file
+    Ok(())
+}
@@ -0,0 +1,128 @@
+// Lucy_Hedgehog's prime counting.
+//
+// S(v) starts as the count of 2..=v and, after processing every prime
+// p <= sqrt(x), equals pi(v). Only the values v = floor(x / i) are ever
+// needed, and there are at most 2 sqrt(x) of them:
+//
+//   - small[v]  holds S(v)       for v <= r
+//   - large[i]  holds S(x / i)   for i <= r
+//
+// Sifting by a prime p removes the numbers whose smallest prime factor is p:
+//
+//   S(v) -= S(v / p) - S(p - 1)   for every v >= p^2
+//
+// Time is O(x^(3/4)) and memory O(sqrt(x)), so x = 10^13 is in reach.
+
+use crate::sieve::{nth_prime_upper_bound, sieve_odd_segment, simple_sieve, SEGMENT_LEN};
+
+pub fn prime_pi(x: u64) -> u64 {
+    if x < 2 {
+        return 0;
+    }
+    let r = x.isqrt() as usize;
+
+    let mut small: Vec<u64> = (0..=r as u64).map(|v| v.saturating_sub(1)).collect();
+    let mut large: Vec<u64> = (0..=r as u64)
+        .map(|i| x.checked_div(i).map_or(0, |v| v - 1))
+        .collect();
+
+    for p in 2..=r {
+        // p is prime iff sifting so far left it in the count
+        if small[p] == small[p - 1] {
+            continue;
+        }
+        let sp = small[p - 1];
+        let p2 = (p * p) as u64;
+
+        // Update in decreasing order of v so S(v / p) is still last round's value
+        let imax = r.min((x / p2) as usize);
+        for i in 1..=imax {
+            let d = i * p;
+            // Invariant: d > r implies x / d <= r
+            let sub = if d <= r { large[d] } else { small[(x / d as u64) as usize] };
+            large[i] -= sub - sp;
+        }
+        for v in (p2 as usize..=r).rev() {
+            small[v] -= small[v / p] - sp;
+        }
+    }
+
+    large[1]
+}
+
+// Lower bound on the nth prime (Dusart):
+//   p_n > n (ln n + ln ln n - 1)   for n >= 2
+fn nth_prime_lower_bound(n: usize) -> u64 {
+    if n < 6 {
+        return 2;
+    }
+    let n = n as f64;
+    (n * (n.ln() + n.ln().ln() - 1.0)) as u64
+}
+
+// Bisection stops once the bracket is this narrow and the rest is sieved
+const SIEVE_WINDOW: u64 = 1 << 22;
+
+// nth prime by bisecting on prime_pi, then sieving the final bracket.
+pub fn nth_prime_pi(n: usize) -> u64 {
+    assert!(n >= 1, "n must be >= 1");
+    if n == 1 {
+        return 2;
+    }
+    let target = n as u64;
+
+    // Invariant: pi(lo) < n <= pi(hi)
+    let mut lo = nth_prime_lower_bound(n).max(2);
+    let mut hi = nth_prime_upper_bound(n);
+    let mut count = prime_pi(lo);
+    while hi - lo > SIEVE_WINDOW {
+        let mid = lo + (hi - lo) / 2;
+        let pi_mid = prime_pi(mid);
+        if pi_mid < target {
+            lo = mid;
+            count = pi_mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    // Walk the odd numbers in (lo, hi] until the count reaches n
+    let base = simple_sieve(hi.isqrt());
+    let mut seg = vec![false; SEGMENT_LEN];
+    let mut low = if lo % 2 == 0 { lo + 1 } else { lo + 2 };
+    loop {
+        sieve_odd_segment(low, &mut seg, &base);
+        for (i, &is_prime) in seg.iter().enumerate() {
+            if is_prime {
+                count += 1;
+                if count == target {
+                    return low + 2 * i as u64;
+                }
+            }
+        }
+        low += 2 * SEGMENT_LEN as u64;
+        debug_assert!(low <= hi + 2 * SEGMENT_LEN as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prime_pi_small_values() {
+        let expected = [0, 0, 1, 2, 2, 3, 3, 4, 4, 4, 4, 5, 5, 6];
+        for (x, &pi) in expected.iter().enumerate() {
+            assert_eq!(prime_pi(x as u64), pi, "x = {x}");
+        }
+    }
+
+    #[test]
+    fn prime_pi_matches_sieve() {
+        let primes = simple_sieve(20_000);
+        for x in (0..20_000_u64).step_by(37) {
+            let expected = primes.iter().take_while(|&&p| p <= x).count() as u64;
+            assert_eq!(prime_pi(x), expected, "x = {x}");
+        }
+    }
+}
@@ -0,0 +1,194 @@
+// Unbounded prime iterators.
+//
+// Two interchangeable backends are provided; both yield every prime in
+// increasing order, forever:
+//
+//   - `SegmentedPrimes`: sieves one cache-sized segment of odd numbers at a
+//     time, growing its base primes as the segments move up. This is the
+//     default returned by `primes()` and `primes_in(..)`.
+//   - `HeapPrimes`: the incremental sieve kept as a priority queue of the
+//     next odd multiple of each prime found so far (a 2-wheel). It needs no
+//     up-front bound but holds one heap entry per prime.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::ops::{Bound, RangeBounds};
+
+use crate::sieve::{sieve_odd_segment, simple_sieve, SEGMENT_LEN};
+
+pub struct SegmentedPrimes {
+    // Whether 2 still has to be yielded
+    two: bool,
+    // First odd number covered by `seg`
+    low: u64,
+    seg: Vec<bool>,
+    // Next index of `seg` to look at
+    pos: usize,
+    base: Vec<u64>,
+    base_limit: u64,
+}
+
+impl SegmentedPrimes {
+    pub fn new() -> Self {
+        Self::starting_at(0)
+    }
+
+    // Every prime >= start
+    pub fn starting_at(start: u64) -> Self {
+        let first_odd = start.max(3) | 1;
+        let mut primes = Self {
+            two: start <= 2,
+            low: first_odd,
+            seg: vec![false; SEGMENT_LEN],
+            pos: 0,
+            base: Vec::new(),
+            base_limit: 0,
+        };
+        primes.fill_segment();
+        primes
+    }
+
+    fn fill_segment(&mut self) {
+        let high = self.low + 2 * SEGMENT_LEN as u64;
+        // Invariant: base holds every prime up to sqrt(high). Grow it
+        // geometrically so re-sieving the base is amortized away.
+        let needed = high.isqrt();
+        if needed > self.base_limit {
+            self.base_limit = needed.max(2 * self.base_limit);
+            self.base = simple_sieve(self.base_limit);
+        }
+        sieve_odd_segment(self.low, &mut self.seg, &self.base);
+        self.pos = 0;
+    }
+}
+
+impl Default for SegmentedPrimes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for SegmentedPrimes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.two {
+            self.two = false;
+            return Some(2);
+        }
+        loop {
+            if let Some(i) = self.seg[self.pos..].iter().position(|&is_prime| is_prime) {
+                let i = self.pos + i;
+                self.pos = i + 1;
+                return Some(self.low + 2 * i as u64);
+            }
+            self.low += 2 * SEGMENT_LEN as u64;
+            self.fill_segment();
+        }
+    }
+}
+
+pub struct HeapPrimes {
+    // Whether 2 still has to be yielded
+    two: bool,
+    // Next odd candidate
+    candidate: u64,
+    // (next odd multiple, step of 2p) for every odd prime found so far
+    multiples: BinaryHeap<Reverse<(u64, u64)>>,
+}
+
+impl HeapPrimes {
+    pub fn new() -> Self {
+        Self { two: true, candidate: 3, multiples: BinaryHeap::new() }
+    }
+}
+
+impl Default for HeapPrimes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for HeapPrimes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.two {
+            self.two = false;
+            return Some(2);
+        }
+        loop {
+            let c = self.candidate;
+            self.candidate += 2;
+
+            let mut composite = false;
+            while let Some(&Reverse((m, step))) = self.multiples.peek() {
+                if m != c {
+                    break;
+                }
+                composite = true;
+                self.multiples.pop();
+                self.multiples.push(Reverse((m + step, step)));
+            }
+
+            if !composite {
+                // Smaller multiples of c have a smaller prime factor
+                self.multiples.push(Reverse((c * c, 2 * c)));
+                return Some(c);
+            }
+        }
+    }
+}
+
+// Every prime, in increasing order
+pub fn primes() -> impl Iterator<Item = u64> {
+    SegmentedPrimes::new()
+}
+
+// The primes inside `range`, in increasing order
+pub fn primes_in<R: RangeBounds<u64>>(range: R) -> impl Iterator<Item = u64> {
+    let start = match range.start_bound() {
+        Bound::Included(&s) => s,
+        Bound::Excluded(&s) => s.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = range.end_bound().cloned();
+    SegmentedPrimes::starting_at(start).take_while(move |&p| match end {
+        Bound::Included(e) => p <= e,
+        Bound::Excluded(e) => p < e,
+        Bound::Unbounded => true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backends_agree_across_segments() {
+        let expected = simple_sieve(300_000);
+        let segmented: Vec<u64> = primes().take(expected.len()).collect();
+        let heap: Vec<u64> = HeapPrimes::new().take(expected.len()).collect();
+        assert_eq!(segmented, expected);
+        assert_eq!(heap, expected);
+    }
+
+    #[test]
+    fn primes_in_respects_bounds() {
+        assert_eq!(primes_in(0..10).collect::<Vec<_>>(), vec![2, 3, 5, 7]);
+        assert_eq!(primes_in(2..=7).collect::<Vec<_>>(), vec![2, 3, 5, 7]);
+        assert_eq!(primes_in(3..7).collect::<Vec<_>>(), vec![3, 5]);
+        assert_eq!(primes_in(14..16).count(), 0);
+        assert_eq!(primes_in(..).nth(4), Some(11));
+        assert_eq!(
+            primes_in(1_000_000..=1_000_100).collect::<Vec<_>>(),
+            vec![1_000_003, 1_000_033, 1_000_037, 1_000_039, 1_000_081, 1_000_099]
+        );
+    }
+
+    #[test]
+    fn skip_while_starts_mid_stream() {
+        let next: Vec<u64> = primes().skip_while(|&p| p < 104_000).take(3).collect();
+        assert_eq!(next, vec![104_003, 104_009, 104_021]);
+    }
+}
@@ -0,0 +1,120 @@
+// Segmented sieve of Eratosthenes over odd numbers.
+//
+// Only the base primes up to sqrt(limit) are kept in memory; the range
+// above them is sieved one cache-sized segment at a time, so memory stays
+// constant no matter how far the sieve has to go.
+
+// One byte per odd number; 32 KiB fits comfortably in L1
+pub(crate) const SEGMENT_LEN: usize = 32 * 1024;
+
+// Primes <= limit by a plain sieve; used for the base primes
+pub(crate) fn simple_sieve(limit: u64) -> Vec<u64> {
+    if limit < 2 {
+        return Vec::new();
+    }
+    let limit = limit as usize;
+    let mut composite = vec![false; limit + 1];
+    let mut primes = vec![2];
+
+    let mut p = 3;
+    while p <= limit {
+        if !composite[p] {
+            primes.push(p as u64);
+            let mut m = p * p;
+            while m <= limit {
+                composite[m] = true;
+                m += 2 * p;
+            }
+        }
+        p += 2;
+    }
+    primes
+}
+
+// Marks the composites among the odd numbers low, low + 2, ..., low + 2(len - 1).
+//
+// Invariants:
+//   - low is odd
+//   - `base` holds every odd prime up to sqrt(low + 2 * len)
+//   - afterwards `seg[i]` is true iff low + 2i is prime (or 1)
+pub(crate) fn sieve_odd_segment(low: u64, seg: &mut [bool], base: &[u64]) {
+    debug_assert!(low % 2 == 1);
+    seg.fill(true);
+    let high = low + 2 * seg.len() as u64;
+
+    for &p in base.iter().filter(|&&p| p != 2) {
+        let pp = p * p;
+        if pp >= high {
+            break;
+        }
+        // First odd multiple of p that is >= max(low, p^2)
+        let mut m = pp.max(low.div_ceil(p) * p);
+        if m % 2 == 0 {
+            m += p;
+        }
+        let mut i = ((m - low) / 2) as usize;
+        while i < seg.len() {
+            seg[i] = false;
+            i += p as usize;
+        }
+    }
+}
+
+// Upper bound on the nth prime:
+//   p_n < n (ln n + ln ln n)   for n >= 6  (Rosser's theorem)
+pub fn nth_prime_upper_bound(n: usize) -> u64 {
+    if n < 6 {
+        return 13;
+    }
+    let n = n as f64;
+    (n * (n.ln() + n.ln().ln())).ceil() as u64
+}
+
+pub fn nth_prime_sieve(n: usize) -> u64 {
+    assert!(n >= 1, "n must be >= 1");
+    if n == 1 {
+        return 2;
+    }
+
+    let bound = nth_prime_upper_bound(n);
+    let base = simple_sieve(bound.isqrt());
+    let mut seg = vec![false; SEGMENT_LEN];
+
+    // 2 is already counted; the segments only hold odd numbers
+    let mut count = 1;
+    let mut low = 3;
+    loop {
+        sieve_odd_segment(low, &mut seg, &base);
+        for (i, &is_prime) in seg.iter().enumerate() {
+            if is_prime {
+                count += 1;
+                if count == n {
+                    return low + 2 * i as u64;
+                }
+            }
+        }
+        low += 2 * SEGMENT_LEN as u64;
+        // Invariant: the bound guarantees we stop before leaving it
+        debug_assert!(low <= bound + 2 * SEGMENT_LEN as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple_sieve_small() {
+        assert_eq!(simple_sieve(1), Vec::<u64>::new());
+        assert_eq!(simple_sieve(2), vec![2]);
+        assert_eq!(simple_sieve(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    }
+
+    #[test]
+    fn nth_prime_sieve_first_primes() {
+        let first = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29];
+        for (i, &p) in first.iter().enumerate() {
+            assert_eq!(nth_prime_sieve(i + 1), p);
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use euler007::{nth_prime, nth_prime_sieve, nth_prime_upper_bound};
+
+#[test]
+fn sieve_finds_expected_answer() {
+    assert_eq!(nth_prime_sieve(6), 13);
+    assert_eq!(nth_prime_sieve(10001), 104743);
+}
+
+#[test]
+fn sieve_matches_trial_division() {
+    let ns = (1..=300).chain([1_000, 4_242, 10_001, 54_321, 99_999, 100_000]);
+    for n in ns {
+        let expected = nth_prime(n);
+        assert_eq!(nth_prime_sieve(n), expected, "n = {n}");
+        assert!(nth_prime_upper_bound(n) >= expected, "n = {n}");
+    }
+}
+
+#[test]
+fn sieve_crosses_many_segments() {
+    assert_eq!(nth_prime_sieve(1_000_000), 15_485_863);
+}
@@ -0,0 +1,46 @@
+use euler007::{nth_prime_pi, nth_prime_sieve, prime_pi};
+
+// pi(10^k) for k = 1..=13
+const PI_POWERS_OF_TEN: [u64; 13] = [
+    4,
+    25,
+    168,
+    1_229,
+    9_592,
+    78_498,
+    664_579,
+    5_761_455,
+    50_847_534,
+    455_052_511,
+    4_118_054_813,
+    37_607_912_018,
+    346_065_536_839,
+];
+
+#[test]
+fn prime_pi_matches_known_powers_of_ten() {
+    for (k, &pi) in PI_POWERS_OF_TEN.iter().enumerate().take(10) {
+        assert_eq!(prime_pi(10_u64.pow(k as u32 + 1)), pi, "k = {}", k + 1);
+    }
+}
+
+// Run with: cargo test --release -- --ignored
+#[test]
+#[ignore]
+fn prime_pi_matches_large_powers_of_ten() {
+    for (k, &pi) in PI_POWERS_OF_TEN.iter().enumerate().skip(10) {
+        assert_eq!(prime_pi(10_u64.pow(k as u32 + 1)), pi, "k = {}", k + 1);
+    }
+}
+
+#[test]
+fn nth_prime_pi_matches_sieve() {
+    for n in (1..=100).chain([10_001, 123_456, 1_000_000]) {
+        assert_eq!(nth_prime_pi(n), nth_prime_sieve(n), "n = {n}");
+    }
+}
+
+#[test]
+fn nth_prime_pi_large() {
+    assert_eq!(nth_prime_pi(100_000_000), 2_038_074_743);
+}
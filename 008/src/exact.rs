@@ -0,0 +1,209 @@
+// Exact window products for any window length.
+//
+// Every non-zero digit factors over the primes 2, 3, 5 and 7, so a window
+// product is fully described by four exponents. The rolling state adds the
+// incoming digit's exponents and subtracts the outgoing ones, which never
+// overflows. Windows are ranked by the logarithm of their product; only the
+// winner is materialized as a big integer.
+
+use num_bigint::BigUint;
+
+const PRIMES: [u32; 4] = [2, 3, 5, 7];
+
+// Exponents of (2, 3, 5, 7) in each digit; 0 is tracked separately
+const DIGIT_EXPONENTS: [[u32; 4]; 10] = [
+    [0, 0, 0, 0],
+    [0, 0, 0, 0],
+    [1, 0, 0, 0],
+    [0, 1, 0, 0],
+    [2, 0, 0, 0],
+    [0, 0, 1, 0],
+    [1, 1, 0, 0],
+    [0, 0, 0, 1],
+    [3, 0, 0, 0],
+    [0, 2, 0, 0],
+];
+
+// Logs closer than this fraction of the larger one are re-checked exactly.
+// The rounding error of `ln` grows with the exponents, so the tolerance
+// has to scale with them; f64 keeps about 1e-16 relative precision.
+const LOG_RELATIVE_EPSILON: f64 = 1e-12;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Exponents([u32; 4]);
+
+impl Exponents {
+    fn add(&mut self, digit: u64) {
+        for (e, d) in self.0.iter_mut().zip(DIGIT_EXPONENTS[digit as usize]) {
+            *e += d;
+        }
+    }
+
+    fn sub(&mut self, digit: u64) {
+        for (e, d) in self.0.iter_mut().zip(DIGIT_EXPONENTS[digit as usize]) {
+            *e -= d;
+        }
+    }
+
+    fn ln(&self) -> f64 {
+        self.0
+            .iter()
+            .zip(PRIMES)
+            .map(|(&e, p)| e as f64 * (p as f64).ln())
+            .sum()
+    }
+
+    fn to_biguint(self) -> BigUint {
+        self.0
+            .iter()
+            .zip(PRIMES)
+            .map(|(&e, p)| BigUint::from(p).pow(e))
+            .product()
+    }
+
+    fn greater_than(&self, other: &Exponents) -> bool {
+        if self == other {
+            return false;
+        }
+        let (a, b) = (self.ln(), other.ln());
+        let diff = a - b;
+        if diff.abs() > LOG_RELATIVE_EPSILON * a.max(b) {
+            diff > 0.0
+        } else {
+            self.to_biguint() > other.to_biguint()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExactWindow {
+    pub product: BigUint,
+    // Index of the first digit of the window in the digit stream
+    pub offset: usize,
+    pub digits: Vec<u64>,
+}
+
+// Same search as `max_product_window` without the u64 overflow, so `None`
+// only means no window fits or a value is not a digit 0..=9.
+// Ties go to the earliest window; if every window holds a zero, the first
+// window is returned with a product of 0.
+pub fn max_product_window_exact(digits: &[u64], k: usize) -> Option<ExactWindow> {
+    if k == 0 || digits.len() < k || !crate::all_digits(digits) {
+        return None;
+    }
+
+    // (exponents, offset) of the best zero-free window so far
+    let mut best: Option<(Exponents, usize)> = None;
+    let mut exps = Exponents::default();
+    let mut zero_count: usize = 0;
+
+    for i in 0..digits.len() {
+        match digits[i] {
+            0 => zero_count += 1,
+            incoming => exps.add(incoming),
+        }
+
+        if i >= k {
+            match digits[i - k] {
+                0 => zero_count -= 1,
+                outgoing => exps.sub(outgoing),
+            }
+        }
+
+        if i + 1 >= k && zero_count == 0 && best.is_none_or(|(b, _)| exps.greater_than(&b)) {
+            best = Some((exps, i + 1 - k));
+        }
+    }
+
+    let (product, offset) = match best {
+        Some((exps, offset)) => (exps.to_biguint(), offset),
+        None => (BigUint::ZERO, 0),
+    };
+    Some(ExactWindow { product, offset, digits: digits[offset..offset + k].to_vec() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{max_product_window, parse_digits};
+
+    const DIGITS: &str = concat!(
+        "73167176531330624919225119674426574742355349194934",
+        "96983520312774506326239578318016984801869478851843",
+        "85861560789112949495459501737958331952853208805511",
+        "12540698747158523863050715693290963295227443043557",
+        "66896648950445244523161731856403098711121722383113",
+        "62229893423380308135336276614282806444486645238749",
+    );
+
+    fn brute(digits: &[u64], k: usize) -> BigUint {
+        digits
+            .windows(k)
+            .map(|w| w.iter().map(|&d| BigUint::from(d)).product::<BigUint>())
+            .max()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn matches_rolling_u64_for_short_windows() {
+        let digits = parse_digits(DIGITS).unwrap();
+        for k in 1..=13 {
+            let exact = max_product_window_exact(&digits, k).unwrap();
+            let rolling = max_product_window(&digits, k).unwrap();
+            assert_eq!(exact.product, BigUint::from(rolling.product), "k = {k}");
+            assert_eq!(exact.offset, rolling.offset, "k = {k}");
+        }
+    }
+
+    #[test]
+    fn long_windows_do_not_overflow() {
+        // 9^120 is far beyond u64::MAX
+        let nines = vec![9_u64; 150];
+        let w = max_product_window_exact(&nines, 120).unwrap();
+        assert_eq!(w.product, BigUint::from(9_u32).pow(120));
+        assert_eq!(w.offset, 0);
+        assert!(w.product > BigUint::from(u64::MAX));
+    }
+
+    #[test]
+    fn long_windows_match_brute_force() {
+        // The longest zero-free runs here are well under 100 digits, so use
+        // an input without zeros to get windows of 100+ digits
+        let digits: Vec<u64> = parse_digits(DIGITS)
+            .unwrap()
+            .into_iter()
+            .filter(|&d| d != 0)
+            .collect();
+        for k in [100, 137, 200] {
+            let w = max_product_window_exact(&digits, k).unwrap();
+            assert_eq!(w.product, brute(&digits, k), "k = {k}");
+            let window_product: BigUint = w.digits.iter().map(|&d| BigUint::from(d)).product();
+            assert_eq!(window_product, w.product);
+        }
+    }
+
+    #[test]
+    fn near_ties_between_huge_products_are_exact() {
+        // 2^282 * 3^274 and 5^57 * 7^208 differ by about 7e-10 in ln. A
+        // shared factor of 2^1000000 leaves that gap but makes the logs big
+        // enough that f64 rounding is of the same order, so only the exact
+        // check can order them.
+        let shared = 1_000_000;
+        let a = Exponents([shared + 282, 274, 0, 0]);
+        let b = Exponents([shared, 0, 57, 208]);
+        let (ln_a, ln_b) = (a.ln(), b.ln());
+        assert!((ln_a - ln_b).abs() <= LOG_RELATIVE_EPSILON * ln_a.max(ln_b));
+        assert!(a.to_biguint() > b.to_biguint());
+        assert!(a.greater_than(&b));
+        assert!(!b.greater_than(&a));
+    }
+
+    #[test]
+    fn equal_products_keep_earliest_window() {
+        // 8 * 8 * 2 * 2 * 2 * 2 = 4 * 4 * 4 * 4 * 4 * 1 = 1024
+        let digits = [8, 8, 2, 2, 2, 2, 0, 4, 4, 4, 4, 4, 1];
+        let w = max_product_window_exact(&digits, 6).unwrap();
+        assert_eq!(w.product, BigUint::from(1024_u32));
+        assert_eq!(w.offset, 0);
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+use std::io::{self, BufRead};
+
+// Digits can be split across lines and grouped with separators; those are
+// skipped so the digits form one continuous stream.
+const SEPARATORS: [char; 4] = [',', '_', ';', '|'];
+
+#[derive(Debug)]
+pub enum DigitParseError {
+    Io(io::Error),
+    // 1-based position of the offending character
+    BadChar { line: usize, column: usize, ch: char },
+}
+
+impl fmt::Display for DigitParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DigitParseError::Io(e) => write!(f, "failed to read input: {e}"),
+            DigitParseError::BadChar { line, column, ch } => {
+                write!(f, "unexpected character {ch:?} at line {line}, column {column}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DigitParseError {}
+
+impl From<io::Error> for DigitParseError {
+    fn from(e: io::Error) -> Self {
+        DigitParseError::Io(e)
+    }
+}
+
+fn push_line_digits(line: &str, line_no: usize, digits: &mut Vec<u64>) -> Result<(), DigitParseError> {
+    for (i, ch) in line.chars().enumerate() {
+        if let Some(d) = ch.to_digit(10) {
+            digits.push(d as u64);
+        } else if !ch.is_whitespace() && !SEPARATORS.contains(&ch) {
+            return Err(DigitParseError::BadChar { line: line_no, column: i + 1, ch });
+        }
+    }
+    Ok(())
+}
+
+pub fn parse_digits(s: &str) -> Result<Vec<u64>, DigitParseError> {
+    let mut digits = Vec::with_capacity(s.len());
+    for (i, line) in s.lines().enumerate() {
+        push_line_digits(line, i + 1, &mut digits)?;
+    }
+    Ok(digits)
+}
+
+// Streams line by line, so a large file is never held as text in full
+pub fn read_digits<R: BufRead>(reader: R) -> Result<Vec<u64>, DigitParseError> {
+    let mut digits = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        push_line_digits(&line?, i + 1, &mut digits)?;
+    }
+    Ok(digits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_newlines_and_separators() {
+        let digits = parse_digits("12 3,4\n5_6|7;8\r\n9\n").unwrap();
+        assert_eq!(digits, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn reports_bad_char_position() {
+        match parse_digits("123\n45x6") {
+            Err(DigitParseError::BadChar { line, column, ch }) => {
+                assert_eq!((line, column, ch), (2, 3, 'x'));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reader_matches_str() {
+        let text = "7316717653\n1330624919\n";
+        assert_eq!(read_digits(text.as_bytes()).unwrap(), parse_digits(text).unwrap());
+    }
+}
@@ -0,0 +1,129 @@
+mod exact;
+mod input;
+pub mod window;
+
+pub use exact::{max_product_window_exact, ExactWindow};
+pub use input::{parse_digits, read_digits, DigitParseError};
+pub use window::{max_product_window_generic, max_sum_window, min_gcd_window};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Window {
+    pub product: u64,
+    // Index of the first digit of the window in the digit stream
+    pub offset: usize,
+    pub digits: Vec<u64>,
+}
+
+fn all_digits(values: &[u64]) -> bool {
+    values.iter().all(|&d| d <= 9)
+}
+
+// The largest window product, or `None` under the same conditions as
+// `max_product_window`
+pub fn max_product_rolling(digits: &[u64], k: usize) -> Option<u64> {
+    max_product_window(digits, k).map(|w| w.product)
+}
+
+// Like `max_product_rolling`, but also reports where the best window is.
+// Ties go to the earliest window; if every window holds a zero, the first
+// window is returned with a product of 0.
+//
+// Returns `None` if no window of length k fits, if a value is not a digit
+// 0..=9, or if the non-zero digits of some window multiply past u64::MAX.
+// Overflow can only happen for k > 20; `max_product_window_exact` handles
+// any length. `max_product_window_generic` takes values above 9.
+pub fn max_product_window(digits: &[u64], k: usize) -> Option<Window> {
+    if k == 0 || digits.len() < k || !all_digits(digits) {
+        return None;
+    }
+
+    let mut best: Option<(u64, usize)> = None;
+    let mut prod: u64 = 1;
+    let mut zero_count: usize = 0;
+
+    for i in 0..digits.len() {
+        // Drop the outgoing digit first, so prod never holds more than one
+        // window's worth of digits
+        if i >= k {
+            match digits[i - k] {
+                0 => zero_count -= 1,
+                outgoing => prod /= outgoing,
+            }
+        }
+
+        match digits[i] {
+            0 => zero_count += 1,
+            incoming => prod = prod.checked_mul(incoming)?,
+        }
+
+        if i + 1 >= k {
+            let current = if zero_count == 0 { prod } else { 0 };
+            if best.is_none_or(|(b, _)| current > b) {
+                best = Some((current, i + 1 - k));
+            }
+        }
+    }
+
+    best.map(|(product, offset)| Window {
+        product,
+        offset,
+        digits: digits[offset..offset + k].to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute(digits: &[u64], k: usize) -> u64 {
+        digits.windows(k).map(|w| w.iter().product()).max().unwrap_or(0)
+    }
+
+    #[test]
+    fn finds_window_and_offset() {
+        let digits = parse_digits("1203989456").unwrap();
+        let w = max_product_window(&digits, 4).unwrap();
+        assert_eq!(w, Window { product: 9 * 8 * 9 * 4, offset: 4, digits: vec![9, 8, 9, 4] });
+    }
+
+    #[test]
+    fn all_windows_with_zero() {
+        let w = max_product_window(&[1, 0, 2, 0, 3], 2).unwrap();
+        assert_eq!((w.product, w.offset), (0, 0));
+        assert_eq!(max_product_window(&[1, 2], 3), None);
+        assert_eq!(max_product_window(&[1, 2], 0), None);
+    }
+
+    #[test]
+    fn overflow_is_reported() {
+        // 9^20 fits in a u64, 9^21 does not
+        let nines = vec![9_u64; 30];
+        assert_eq!(max_product_rolling(&nines, 20), Some(9_u64.pow(20)));
+        assert_eq!(max_product_window(&nines, 21), None);
+        assert_eq!(max_product_rolling(&nines, 21), None);
+        // A zero in every window does not save the non-zero digits
+        let mut digits = vec![9_u64; 22];
+        digits[0] = 0;
+        assert_eq!(max_product_window(&digits, 22), None);
+        assert_eq!(max_product_rolling(&[], 1), None);
+    }
+
+    #[test]
+    fn non_digits_are_rejected() {
+        assert_eq!(max_product_window(&[1, 2, 12], 2), None);
+        assert_eq!(max_product_rolling(&[10, 1, 1], 1), None);
+        assert_eq!(max_product_window_exact(&[1, 2, 12], 2), None);
+        assert_eq!(max_product_window_generic(&[1, 2, 12], 2), Some((24, 1)));
+        assert_eq!(max_product_rolling(&[1, 2, 9], 2), Some(18));
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let digits = parse_digits("73167176531330624919225119674426574742355349194934").unwrap();
+        for k in 1..=13 {
+            let w = max_product_window(&digits, k).unwrap();
+            assert_eq!(w.product, brute(&digits, k), "k = {k}");
+            assert_eq!(w.digits.iter().product::<u64>(), w.product);
+        }
+    }
+}
@@ -1,68 +1,87 @@
+use std::env;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufReader};
 
+use euler008::{
+    max_product_window, max_product_window_exact, parse_digits, read_digits, DigitParseError,
+};
 
-fn max_product_rolling(digits: &[u64], k: usize) -> u64 {
-    if k == 0 || digits.len() < k {
-        return 0;
-    }
+const MAGIC_NUMBER: &str = concat!(
+    "73167176531330624919225119674426574742355349194934",
+    "96983520312774506326239578318016984801869478851843",
+    "85861560789112949495459501737958331952853208805511",
+    "12540698747158523863050715693290963295227443043557",
+    "66896648950445244523161731856403098711121722383113",
+    "62229893423380308135336276614282806444486645238749",
+    "30358907296290491560440772390713810515859307960866",
+    "70172427121883998797908792274921901699720888093776",
+    "65727333001053367881220235421809751254540594752243",
+    "52584907711670556013604839586446706324415722155397",
+    "53697817977846174064955149290862569321978468622482",
+    "83972241375657056057490261407972968652414535100474",
+    "82166370484403199890008895243450658541227588666881",
+    "16427171479924442928230863465674813919123162824586",
+    "17866458359124566529476545682848912883142607690042",
+    "24219022671055626321111109370544217506941658960408",
+    "07198403850962455444362981230987879927244284909188",
+    "84580156166097919133875499200524063689912560717606",
+    "05886116467109405077541002256983155200055935729725",
+    "71636269561882670428252483600823257530420752963450",
+);
 
-    let mut best: u64 = 0;
-    let mut prod: u64 = 1;
-    let mut zero_count: usize = 0;
+enum Source {
+    Magic,
+    Stdin,
+    File(String),
+    Digits(String),
+}
 
-    for i in 0..digits.len() {
-        let incoming = digits[i];
-        if incoming == 0 {
-            zero_count += 1;
-        } else {
-            prod *= incoming;
-        }
+struct Args {
+    source: Source,
+    seq_length: usize,
+    verbose: bool,
+    exact: bool,
+}
 
-        if i >= k {
-            let outgoing = digits[i - k];
-            if outgoing == 0 {
-                zero_count -= 1;
-            } else {
-                prod /= outgoing;
-            }
-        }
+const USAGE: &str = "usage: euler008 [-k LEN] [-v] [-e] [--file PATH | --stdin | DIGITS]";
+
+fn parse_args() -> Result<Args, Box<dyn Error>> {
+    let mut args = Args { source: Source::Magic, seq_length: 13, verbose: false, exact: false };
+    let mut it = env::args().skip(1);
 
-        if i + 1 >= k && zero_count == 0 {
-            if prod > best {
-                best = prod;
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "-k" | "--window" => {
+                let v = it.next().ok_or("missing value for --window")?;
+                args.seq_length = v.parse().map_err(|e| format!("Invalid window length {v:?}: {e}"))?;
             }
+            "-f" | "--file" => {
+                args.source = Source::File(it.next().ok_or("missing value for --file")?);
+            }
+            "--stdin" | "-" => args.source = Source::Stdin,
+            "-v" | "--verbose" => args.verbose = true,
+            "-e" | "--exact" => args.exact = true,
+            "-h" | "--help" => return Err(USAGE.into()),
+            s if s.starts_with('-') => return Err(format!("Unknown flag {s:?}\n{USAGE}").into()),
+            _ => args.source = Source::Digits(arg),
         }
     }
-    return best;
+    Ok(args)
+}
+
+fn load_digits(source: Source) -> Result<Vec<u64>, DigitParseError> {
+    match source {
+        Source::Magic => parse_digits(MAGIC_NUMBER),
+        Source::Stdin => read_digits(io::stdin().lock()),
+        Source::File(path) => read_digits(BufReader::new(File::open(path)?)),
+        Source::Digits(s) => parse_digits(&s),
+    }
 }
 
-fn main() {
-    let magic_number = concat!(
-        "73167176531330624919225119674426574742355349194934",
-        "96983520312774506326239578318016984801869478851843",
-        "85861560789112949495459501737958331952853208805511",
-        "12540698747158523863050715693290963295227443043557",
-        "66896648950445244523161731856403098711121722383113",
-        "62229893423380308135336276614282806444486645238749",
-        "30358907296290491560440772390713810515859307960866",
-        "70172427121883998797908792274921901699720888093776",
-        "65727333001053367881220235421809751254540594752243",
-        "52584907711670556013604839586446706324415722155397",
-        "53697817977846174064955149290862569321978468622482",
-        "83972241375657056057490261407972968652414535100474",
-        "82166370484403199890008895243450658541227588666881",
-        "16427171479924442928230863465674813919123162824586",
-        "17866458359124566529476545682848912883142607690042",
-        "24219022671055626321111109370544217506941658960408",
-        "07198403850962455444362981230987879927244284909188",
-        "84580156166097919133875499200524063689912560717606",
-        "05886116467109405077541002256983155200055935729725",
-        "71636269561882670428252483600823257530420752963450",
-    );
-    let seq_length: usize = 13;
-    let digits: Vec<u64> = magic_number
-        .bytes()
-        .map(|b| (b - b'0') as u64)
-        .collect();
+fn main() -> Result<(), Box<dyn Error>> {
+    let Args { source, seq_length, verbose, exact } = parse_args()?;
+    let digits = load_digits(source).map_err(|e| format!("Input error: {e}"))?;
 
     // Imperative style
     // let mut best: u64 = 0;
@@ -98,7 +117,24 @@ fn main() {
     //     .unwrap_or(0);
 
     // More performant for large seq_length
-    let best = max_product_rolling(&digits, seq_length);
+    // Windows whose product may not fit in a u64 always use the exact search
+    let needs_exact = u32::try_from(seq_length).ok().and_then(|k| 9_u64.checked_pow(k)).is_none();
+    let best = if exact || needs_exact {
+        max_product_window_exact(&digits, seq_length)
+            .map(|w| (w.product.to_string(), w.offset, w.digits))
+    } else {
+        max_product_window(&digits, seq_length)
+            .map(|w| (w.product.to_string(), w.offset, w.digits))
+    };
+    let (product, offset, window) = best.ok_or_else(|| {
+        format!("Window length {seq_length} must be between 1 and the digit count ({})", digits.len())
+    })?;
 
-    println!("{best}");
-}
\ No newline at end of file
+    println!("{product}");
+    if verbose {
+        let window: String = window.iter().map(|d| d.to_string()).collect();
+        println!("offset: {offset}");
+        println!("digits: {window}");
+    }
+    Ok(())
+}
@@ -0,0 +1,381 @@
+// Sliding-window aggregation over a monoid.
+//
+// A window of length k slides over the input; after each step the
+// aggregate of the k values inside it is available. Two engines are
+// provided behind `SlidingWindow`:
+//
+//   - `InvertibleWindow` keeps one running aggregate and takes the outgoing
+//     value back out with `Invertible::uncombine`, so every step is O(1).
+//     Sum and product-with-zero-counting (as in `max_product_rolling`)
+//     work this way.
+//   - `TwoStackWindow` handles operations that cannot be undone (min, max,
+//     gcd). The back stack keeps a running aggregate of pushes; the front
+//     stack keeps suffix aggregates of the oldest values. When the front
+//     runs dry the back is flipped into it, so each value is combined a
+//     constant number of times: amortized O(1) per step.
+
+use std::collections::VecDeque;
+
+pub trait Monoid {
+    type Value: Clone;
+
+    fn identity(&self) -> Self::Value;
+    // Must be associative; `a` is always the older side of the window
+    fn combine(&self, a: &Self::Value, b: &Self::Value) -> Self::Value;
+}
+
+pub trait Invertible: Monoid {
+    // Removes `oldest` from the front of `acc`
+    fn uncombine(&self, acc: &Self::Value, oldest: &Self::Value) -> Self::Value;
+}
+
+pub trait SlidingWindow {
+    type Value: Clone;
+
+    fn push(&mut self, v: Self::Value);
+    // Drops the oldest value
+    fn pop(&mut self);
+    fn len(&self) -> usize;
+    fn query(&self) -> Self::Value;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub struct InvertibleWindow<M: Invertible> {
+    monoid: M,
+    values: VecDeque<M::Value>,
+    acc: M::Value,
+}
+
+impl<M: Invertible> InvertibleWindow<M> {
+    pub fn new(monoid: M) -> Self {
+        let acc = monoid.identity();
+        Self { monoid, values: VecDeque::new(), acc }
+    }
+}
+
+impl<M: Invertible> SlidingWindow for InvertibleWindow<M> {
+    type Value = M::Value;
+
+    fn push(&mut self, v: M::Value) {
+        self.acc = self.monoid.combine(&self.acc, &v);
+        self.values.push_back(v);
+    }
+
+    fn pop(&mut self) {
+        if let Some(oldest) = self.values.pop_front() {
+            self.acc = self.monoid.uncombine(&self.acc, &oldest);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn query(&self) -> M::Value {
+        self.acc.clone()
+    }
+}
+
+pub struct TwoStackWindow<M: Monoid> {
+    monoid: M,
+    // (value, aggregate of this value and everything newer in the front stack);
+    // the top is the oldest value in the window
+    front: Vec<(M::Value, M::Value)>,
+    back: Vec<M::Value>,
+    back_acc: M::Value,
+}
+
+impl<M: Monoid> TwoStackWindow<M> {
+    pub fn new(monoid: M) -> Self {
+        let back_acc = monoid.identity();
+        Self { monoid, front: Vec::new(), back: Vec::new(), back_acc }
+    }
+
+    fn flip(&mut self) {
+        let mut acc = self.monoid.identity();
+        while let Some(v) = self.back.pop() {
+            acc = self.monoid.combine(&v, &acc);
+            self.front.push((v, acc.clone()));
+        }
+        self.back_acc = self.monoid.identity();
+    }
+}
+
+impl<M: Monoid> SlidingWindow for TwoStackWindow<M> {
+    type Value = M::Value;
+
+    fn push(&mut self, v: M::Value) {
+        self.back_acc = self.monoid.combine(&self.back_acc, &v);
+        self.back.push(v);
+    }
+
+    fn pop(&mut self) {
+        if self.front.is_empty() {
+            self.flip();
+        }
+        self.front.pop();
+    }
+
+    fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    fn query(&self) -> M::Value {
+        match self.front.last() {
+            Some((_, front_acc)) => self.monoid.combine(front_acc, &self.back_acc),
+            None => self.back_acc.clone(),
+        }
+    }
+}
+
+// Aggregate of every k-window, indexed by the window's offset
+pub fn window_aggregates<W: SlidingWindow>(mut window: W, values: &[W::Value], k: usize) -> Vec<W::Value> {
+    if k == 0 || values.len() < k {
+        return Vec::new();
+    }
+    let mut out = Vec::with_capacity(values.len() - k + 1);
+    for v in values {
+        // Drop the oldest value first, so an invertible aggregate never
+        // holds more than k values
+        if window.len() == k {
+            window.pop();
+        }
+        window.push(v.clone());
+        if window.len() == k {
+            out.push(window.query());
+        }
+    }
+    out
+}
+
+pub struct Sum;
+
+impl Monoid for Sum {
+    type Value = u64;
+
+    fn identity(&self) -> u64 {
+        0
+    }
+
+    fn combine(&self, a: &u64, b: &u64) -> u64 {
+        a + b
+    }
+}
+
+impl Invertible for Sum {
+    fn uncombine(&self, acc: &u64, oldest: &u64) -> u64 {
+        acc - oldest
+    }
+}
+
+// Product of the non-zero values plus a count of zeros, so a zero can be
+// taken back out of the window without dividing by it. The product is
+// `None` once it has passed u64::MAX; dividing cannot undo that, so it
+// stays `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZeroCounted {
+    pub product: Option<u64>,
+    pub zeros: usize,
+}
+
+impl ZeroCounted {
+    pub fn lift(v: u64) -> Self {
+        match v {
+            0 => Self { product: Some(1), zeros: 1 },
+            v => Self { product: Some(v), zeros: 0 },
+        }
+    }
+
+    // `None` if the non-zero values overflowed, even when a zero is present
+    pub fn value(&self) -> Option<u64> {
+        let product = self.product?;
+        Some(if self.zeros == 0 { product } else { 0 })
+    }
+}
+
+pub struct Product;
+
+impl Monoid for Product {
+    type Value = ZeroCounted;
+
+    fn identity(&self) -> ZeroCounted {
+        ZeroCounted { product: Some(1), zeros: 0 }
+    }
+
+    fn combine(&self, a: &ZeroCounted, b: &ZeroCounted) -> ZeroCounted {
+        let product = a.product.zip(b.product).and_then(|(a, b)| a.checked_mul(b));
+        ZeroCounted { product, zeros: a.zeros + b.zeros }
+    }
+}
+
+impl Invertible for Product {
+    fn uncombine(&self, acc: &ZeroCounted, oldest: &ZeroCounted) -> ZeroCounted {
+        let product = acc.product.zip(oldest.product).map(|(acc, oldest)| acc / oldest);
+        ZeroCounted { product, zeros: acc.zeros - oldest.zeros }
+    }
+}
+
+pub struct Min;
+
+impl Monoid for Min {
+    type Value = u64;
+
+    fn identity(&self) -> u64 {
+        u64::MAX
+    }
+
+    fn combine(&self, a: &u64, b: &u64) -> u64 {
+        *a.min(b)
+    }
+}
+
+pub struct Max;
+
+impl Monoid for Max {
+    type Value = u64;
+
+    fn identity(&self) -> u64 {
+        0
+    }
+
+    fn combine(&self, a: &u64, b: &u64) -> u64 {
+        *a.max(b)
+    }
+}
+
+pub struct Gcd;
+
+impl Monoid for Gcd {
+    type Value = u64;
+
+    // gcd(0, x) = x
+    fn identity(&self) -> u64 {
+        0
+    }
+
+    fn combine(&self, a: &u64, b: &u64) -> u64 {
+        let (mut a, mut b) = (*a, *b);
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    }
+}
+
+// (best value, offset); ties go to the earliest window
+fn best_by<F>(aggs: impl IntoIterator<Item = u64>, better: F) -> Option<(u64, usize)>
+where
+    F: Fn(u64, u64) -> bool,
+{
+    aggs.into_iter()
+        .enumerate()
+        .fold(None, |best, (offset, v)| match best {
+            Some((b, _)) if !better(v, b) => best,
+            _ => Some((v, offset)),
+        })
+}
+
+pub fn max_sum_window(digits: &[u64], k: usize) -> Option<(u64, usize)> {
+    let aggs = window_aggregates(InvertibleWindow::new(Sum), digits, k);
+    best_by(aggs, |a, b| a > b)
+}
+
+// `None` under the same conditions as `max_product_window`, including
+// when some window's non-zero digits overflow a u64
+pub fn max_product_window_generic(digits: &[u64], k: usize) -> Option<(u64, usize)> {
+    let lifted: Vec<ZeroCounted> = digits.iter().map(|&d| ZeroCounted::lift(d)).collect();
+    let aggs = window_aggregates(InvertibleWindow::new(Product), &lifted, k);
+    let values: Vec<u64> = aggs.iter().map(ZeroCounted::value).collect::<Option<_>>()?;
+    best_by(values, |a, b| a > b)
+}
+
+pub fn min_gcd_window(values: &[u64], k: usize) -> Option<(u64, usize)> {
+    let aggs = window_aggregates(TwoStackWindow::new(Gcd), values, k);
+    best_by(aggs, |a, b| a < b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{max_product_window, parse_digits};
+
+    fn naive<M: Monoid>(m: &M, values: &[M::Value], k: usize) -> Vec<M::Value> {
+        values
+            .windows(k)
+            .map(|w| w.iter().fold(m.identity(), |acc, v| m.combine(&acc, v)))
+            .collect()
+    }
+
+    fn sample() -> Vec<u64> {
+        // Deterministic pseudo-random values with repeats and zeros
+        let mut x = 12345_u64;
+        (0..300)
+            .map(|_| {
+                x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (x >> 33) % 60
+            })
+            .collect()
+    }
+
+    #[test]
+    fn engines_match_naive_aggregation() {
+        let values = sample();
+        for k in [1, 2, 7, 31, 300] {
+            assert_eq!(window_aggregates(InvertibleWindow::new(Sum), &values, k), naive(&Sum, &values, k));
+            assert_eq!(window_aggregates(TwoStackWindow::new(Sum), &values, k), naive(&Sum, &values, k));
+            assert_eq!(window_aggregates(TwoStackWindow::new(Min), &values, k), naive(&Min, &values, k));
+            assert_eq!(window_aggregates(TwoStackWindow::new(Max), &values, k), naive(&Max, &values, k));
+            assert_eq!(window_aggregates(TwoStackWindow::new(Gcd), &values, k), naive(&Gcd, &values, k));
+        }
+    }
+
+    #[test]
+    fn two_stack_keeps_order_for_non_commutative_monoids() {
+        struct Concat;
+        impl Monoid for Concat {
+            type Value = String;
+            fn identity(&self) -> String {
+                String::new()
+            }
+            fn combine(&self, a: &String, b: &String) -> String {
+                format!("{a}{b}")
+            }
+        }
+        let values: Vec<String> = "abcdef".chars().map(String::from).collect();
+        let aggs = window_aggregates(TwoStackWindow::new(Concat), &values, 3);
+        assert_eq!(aggs, vec!["abc", "bcd", "cde", "def"]);
+    }
+
+    #[test]
+    fn generic_product_matches_rolling() {
+        let digits = parse_digits("73167176531330624919225119674426574742355349194934").unwrap();
+        for k in 1..=13 {
+            let rolling = max_product_window(&digits, k).unwrap();
+            assert_eq!(max_product_window_generic(&digits, k), Some((rolling.product, rolling.offset)));
+        }
+    }
+
+    #[test]
+    fn generic_product_reports_overflow() {
+        // Same boundary as max_product_window: 9^20 fits in a u64, 9^21 does not
+        let nines = [9_u64; 30];
+        assert_eq!(max_product_window_generic(&nines, 20), Some((9_u64.pow(20), 0)));
+        assert_eq!(max_product_window_generic(&nines, 21), None);
+        assert_eq!(max_product_window(&nines, 21), None);
+        let mut digits = [9_u64; 22];
+        digits[0] = 0;
+        assert_eq!(max_product_window_generic(&digits, 22), None);
+    }
+
+    #[test]
+    fn max_sum_and_min_gcd() {
+        assert_eq!(max_sum_window(&[1, 9, 9, 0, 8, 9, 9], 3), Some((26, 4)));
+        assert_eq!(min_gcd_window(&[12, 18, 24, 36], 2), Some((6, 0)));
+        assert_eq!(min_gcd_window(&[12, 18, 24, 36, 7, 14], 2), Some((1, 3)));
+        assert_eq!(min_gcd_window(&[12, 18, 24, 36, 7, 14], 3), Some((1, 2)));
+        assert_eq!(max_sum_window(&[1, 2], 3), None);
+    }
+}
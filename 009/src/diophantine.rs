@@ -0,0 +1,221 @@
+// Searches for equations related to a^2 + b^2 = c^2.
+//
+// Each search follows the same pattern as `candidate_n`: rewrite the
+// equation so one side factors, walk the divisors of the other side, and
+// keep only the candidates that satisfy the invariants.
+
+use crate::triples::gcd;
+
+fn divisors(n: u64) -> Vec<u64> {
+    let mut small = Vec::new();
+    let mut large = Vec::new();
+    for d in (1..).take_while(|&d| d * d <= n) {
+        if n % d == 0 {
+            small.push(d);
+            if d * d != n {
+                large.push(n / d);
+            }
+        }
+    }
+    small.extend(large.into_iter().rev());
+    small
+}
+
+// All (leg, b, c) with leg^2 + b^2 = c^2, ordered by b.
+//
+//   leg^2 = c^2 - b^2 = (c - b)(c + b) = d * e
+//
+// Invariants:
+//   - d divides leg^2 and d < e (so b >= 1)
+//   - d and e have the same parity (so b and c are integers)
+pub fn triples_with_leg(leg: u64) -> Vec<(u64, u64, u64)> {
+    let l2 = leg * leg;
+    let mut found: Vec<(u64, u64, u64)> = (1..leg)
+        // Invariant: d must divide leg^2
+        .filter(|&d| l2 % d == 0)
+        .map(|d| (d, l2 / d))
+        // Invariant: d and e share parity
+        .filter(|&(d, e)| (e - d) % 2 == 0)
+        .map(|(d, e)| (leg, (e - d) / 2, (e + d) / 2))
+        .collect();
+    found.sort_unstable();
+    found
+}
+
+// All (a, b, hyp) with a < b and a^2 + b^2 = hyp^2, ordered by a.
+//
+// Each is k times a primitive triple with hypotenuse h = m^2 + n^2, so:
+//   - h must divide hyp, and k = hyp / h
+//   - m^2 + n^2 = h with m > n >= 1, gcd(m, n) = 1 and m - n odd
+pub fn triples_with_hypotenuse(hyp: u64) -> Vec<(u64, u64, u64)> {
+    let mut found = Vec::new();
+    for h in divisors(hyp) {
+        let k = hyp / h;
+        // Invariant: n < m  =>  m^2 > h / 2
+        for m in ((h / 2).isqrt() + 1..=h.isqrt()).filter(|&m| m * m < h) {
+            let n2 = h - m * m;
+            let n = n2.isqrt();
+            if n * n != n2 || n >= m || (m - n) % 2 == 0 || gcd(m, n) != 1 {
+                continue;
+            }
+            let (a, b) = (m * m - n * n, 2 * m * n);
+            found.push((k * a.min(b), k * a.max(b), hyp));
+        }
+    }
+    found.sort_unstable();
+    found
+}
+
+// All (a, b, c) with a < b, a^2 + ab + b^2 = c^2 and c <= bound: the integer
+// triangles with a 120 degree angle opposite c. Ordered by (a, b).
+//
+// Primitive solutions are
+//   a = m^2 - n^2,  b = 2mn + n^2,  c = m^2 + mn + n^2
+// for m > n >= 1, gcd(m, n) = 1 and m - n not divisible by 3; every
+// other solution is a multiple k of one of those.
+pub fn eisenstein_triples_120(bound: u64) -> Vec<(u64, u64, u64)> {
+    let mut found = Vec::new();
+    // Bound invariant: n = 1 gives the smallest c for each m
+    for m in (2..).take_while(|&m| m * m + m < bound) {
+        for n in (1..m).filter(|&n| gcd(m, n) == 1 && (m - n) % 3 != 0) {
+            let (a, b, c) = (m * m - n * n, 2 * m * n + n * n, m * m + m * n + n * n);
+            for k in (1..).take_while(|&k| k * c <= bound) {
+                found.push((k * a.min(b), k * a.max(b), k * c));
+            }
+        }
+    }
+    found.sort_unstable();
+    found
+}
+
+// All (a, b, c) with a <= b, a^2 - ab + b^2 = c^2 and a, b, c <= bound: the
+// integer triangles with a 60 degree angle opposite c. Ordered by (a, b).
+//
+// Besides the equilateral (k, k, k), each comes from a 120 degree triple
+// (x, y, c): both (x, x + y, c) and (y, x + y, c) satisfy the equation.
+pub fn eisenstein_triples_60(bound: u64) -> Vec<(u64, u64, u64)> {
+    let mut found: Vec<(u64, u64, u64)> = (1..=bound).map(|k| (k, k, k)).collect();
+    for (x, y, c) in eisenstein_triples_120(bound) {
+        // Invariant: x + y > c is the largest side
+        if x + y <= bound {
+            found.push((x, x + y, c));
+            found.push((y, x + y, c));
+        }
+    }
+    found.sort_unstable();
+    found
+}
+
+// All (a, b, c, d) with a <= b <= c, a^2 + b^2 + c^2 = d^2 and d <= bound.
+//
+//   a^2 + b^2 = d^2 - c^2 = (d - c)(d + c) = p * q
+//
+// Invariants:
+//   - p divides a^2 + b^2 and p < q
+//   - p and q have the same parity (so c and d are integers)
+//   - c = (q - p) / 2 >= b
+//   - d = (q + p) / 2 <= bound
+pub fn pythagorean_quadruples(bound: u64) -> Vec<(u64, u64, u64, u64)> {
+    let mut found = Vec::new();
+    // Bound invariant: a <= b <= c, so 3a^2 <= d^2 and a^2 + 2b^2 <= d^2
+    for a in (1..).take_while(|&a| 3 * a * a <= bound * bound) {
+        for b in (a..).take_while(|&b| a * a + 2 * b * b <= bound * bound) {
+            let s = a * a + b * b;
+            for p in (1..).take_while(|&p| p * p < s).filter(|&p| s % p == 0) {
+                let q = s / p;
+                if (q - p) % 2 != 0 {
+                    continue;
+                }
+                let (c, d) = ((q - p) / 2, (q + p) / 2);
+                if c >= b && d <= bound {
+                    found.push((a, b, c, d));
+                }
+            }
+        }
+    }
+    found.sort_unstable();
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUND: u64 = 120;
+
+    // (a, b, c) with a <= b, both <= limit, and f(a, b) = c^2
+    fn brute_pairs(limit: u64, f: impl Fn(u64, u64) -> u64) -> Vec<(u64, u64, u64)> {
+        let mut found = Vec::new();
+        for a in 1..=limit {
+            for b in a..=limit {
+                let c2 = f(a, b);
+                let c = c2.isqrt();
+                if c * c == c2 {
+                    found.push((a, b, c));
+                }
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn leg_matches_brute_force() {
+        for leg in 1..=BOUND {
+            // b < leg^2 / 2 since c - b >= 1
+            let want: Vec<_> = (1..=leg * leg / 2)
+                .filter_map(|b| {
+                    let c2 = leg * leg + b * b;
+                    let c = c2.isqrt();
+                    (c * c == c2).then_some((leg, b, c))
+                })
+                .collect();
+            assert_eq!(triples_with_leg(leg), want, "leg = {leg}");
+        }
+    }
+
+    #[test]
+    fn hypotenuse_matches_brute_force() {
+        let all = brute_pairs(BOUND, |a, b| a * a + b * b);
+        for hyp in 1..=BOUND {
+            let want: Vec<_> = all.iter().copied().filter(|&(a, b, c)| c == hyp && a < b).collect();
+            assert_eq!(triples_with_hypotenuse(hyp), want, "hyp = {hyp}");
+        }
+        assert_eq!(triples_with_hypotenuse(25), vec![(7, 24, 25), (15, 20, 25)]);
+    }
+
+    #[test]
+    fn eisenstein_120_matches_brute_force() {
+        let want: Vec<_> = brute_pairs(BOUND, |a, b| a * a + a * b + b * b)
+            .into_iter()
+            .filter(|&(_, _, c)| c <= BOUND)
+            .collect();
+        assert_eq!(eisenstein_triples_120(BOUND), want);
+        assert_eq!(eisenstein_triples_120(7), vec![(3, 5, 7)]);
+    }
+
+    #[test]
+    fn eisenstein_60_matches_brute_force() {
+        let want = brute_pairs(BOUND, |a, b| a * a + b * b - a * b);
+        assert_eq!(eisenstein_triples_60(BOUND), want);
+        assert!(eisenstein_triples_60(8).contains(&(3, 8, 7)));
+    }
+
+    #[test]
+    fn quadruples_match_brute_force() {
+        let bound: u64 = 60;
+        let mut want = Vec::new();
+        for a in 1..bound {
+            for b in a..bound {
+                for c in b..bound {
+                    let d2 = a * a + b * b + c * c;
+                    let d = d2.isqrt();
+                    if d * d == d2 && d <= bound {
+                        want.push((a, b, c, d));
+                    }
+                }
+            }
+        }
+        assert_eq!(pythagorean_quadruples(bound), want);
+        assert_eq!(pythagorean_quadruples(3), vec![(1, 2, 2, 3)]);
+    }
+}
@@ -0,0 +1,172 @@
+// Berggren's (Barning's) tree of primitive Pythagorean triples.
+//
+// Starting from (3, 4, 5), every primitive triple appears exactly once as a
+// node, and each node has three children:
+//
+//   A: ( a - 2b + 2c,   2a - b + 2c,   2a - 2b + 3c)
+//   B: ( a + 2b + 2c,   2a + b + 2c,   2a + 2b + 3c)
+//   C: (-a + 2b + 2c,  -2a + b + 2c,  -2a + 2b + 3c)
+//
+// Children always have a larger hypotenuse and perimeter than their parent,
+// so a bound on either prunes whole subtrees.
+
+use std::collections::VecDeque;
+
+use crate::triples::gcd;
+use crate::triple;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TripleBound {
+    // a + b + c <= limit
+    Perimeter(u64),
+    // c <= limit
+    Hypotenuse(u64),
+}
+
+impl TripleBound {
+    fn admits(&self, (a, b, c): (u64, u64, u64)) -> bool {
+        match *self {
+            TripleBound::Perimeter(limit) => a + b + c <= limit,
+            TripleBound::Hypotenuse(limit) => c <= limit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeOrder {
+    BreadthFirst,
+    DepthFirst,
+}
+
+// Invariant: c > a and c > b, so every subtraction below stays positive
+fn children((a, b, c): (u64, u64, u64)) -> [(u64, u64, u64); 3] {
+    [
+        (a + 2 * (c - b), 2 * a + (2 * c - b), 2 * a + 2 * (c - b) + c),
+        (a + 2 * b + 2 * c, 2 * a + b + 2 * c, 2 * a + 2 * b + 3 * c),
+        (2 * b + (2 * c - a), b + 2 * (c - a), 2 * b + 2 * (c - a) + c),
+    ]
+}
+
+// Primitive triples within a bound, yielded as (a, b, c) with a < b < c.
+pub struct PrimitiveTriples {
+    pending: VecDeque<(u64, u64, u64)>,
+    order: TreeOrder,
+    bound: TripleBound,
+}
+
+impl PrimitiveTriples {
+    pub fn new(bound: TripleBound, order: TreeOrder) -> Self {
+        let mut pending = VecDeque::new();
+        let root = (3, 4, 5);
+        if bound.admits(root) {
+            pending.push_back(root);
+        }
+        Self { pending, order, bound }
+    }
+}
+
+impl Iterator for PrimitiveTriples {
+    type Item = (u64, u64, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = match self.order {
+            TreeOrder::BreadthFirst => self.pending.pop_front()?,
+            TreeOrder::DepthFirst => self.pending.pop_back()?,
+        };
+
+        let kids = children(node).into_iter().filter(|&t| self.bound.admits(t));
+        match self.order {
+            TreeOrder::BreadthFirst => self.pending.extend(kids),
+            // Reversed so the A branch is visited first
+            TreeOrder::DepthFirst => self.pending.extend(kids.rev()),
+        }
+
+        let (a, b, c) = node;
+        Some((a.min(b), a.max(b), c))
+    }
+}
+
+// The same set of triples from Euclid's formula, keeping only the (m, n)
+// pairs that give primitive triples: gcd(m, n) = 1 and m - n odd.
+pub fn primitive_triples_euclid(bound: TripleBound) -> Vec<(u64, u64, u64)> {
+    let mut found = Vec::new();
+    // Bound invariant: n = 1 gives the smallest triple for each m,
+    // with perimeter 2m(m + 1) and hypotenuse m^2 + 1
+    let m_fits = |m: u64| match bound {
+        TripleBound::Perimeter(limit) => 2 * m * (m + 1) <= limit,
+        TripleBound::Hypotenuse(limit) => m * m < limit,
+    };
+    for m in (2..).take_while(|&m| m_fits(m)) {
+        for n in (1..m).filter(|&n| (m - n) % 2 == 1 && gcd(m, n) == 1) {
+            let t = triple(m, n);
+            if bound.admits(t) {
+                let (a, b, c) = t;
+                found.push((a.min(b), a.max(b), c));
+            }
+        }
+    }
+    found
+}
+
+// Number of perimeters p <= limit made by exactly one Pythagorean triple
+pub fn perimeters_with_one_triple(limit: u64) -> usize {
+    let mut counts = vec![0_u8; limit as usize + 1];
+    for (a, b, c) in PrimitiveTriples::new(TripleBound::Perimeter(limit), TreeOrder::DepthFirst) {
+        let p = (a + b + c) as usize;
+        for multiple in (p..counts.len()).step_by(p) {
+            counts[multiple] = counts[multiple].saturating_add(1);
+        }
+    }
+    counts.iter().filter(|&&n| n == 1).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut v: Vec<(u64, u64, u64)>) -> Vec<(u64, u64, u64)> {
+        v.sort_unstable();
+        v
+    }
+
+    #[test]
+    fn first_levels_breadth_first() {
+        let first: Vec<_> = PrimitiveTriples::new(TripleBound::Hypotenuse(100), TreeOrder::BreadthFirst)
+            .take(4)
+            .collect();
+        assert_eq!(first, vec![(3, 4, 5), (5, 12, 13), (20, 21, 29), (8, 15, 17)]);
+    }
+
+    #[test]
+    fn tree_matches_euclid() {
+        for bound in [
+            TripleBound::Perimeter(0),
+            TripleBound::Perimeter(12),
+            TripleBound::Perimeter(10_000),
+            TripleBound::Hypotenuse(5),
+            TripleBound::Hypotenuse(10_000),
+        ] {
+            let euclid = sorted(primitive_triples_euclid(bound));
+            for order in [TreeOrder::BreadthFirst, TreeOrder::DepthFirst] {
+                let tree = sorted(PrimitiveTriples::new(bound, order).collect());
+                assert_eq!(tree, euclid, "{bound:?} {order:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn tree_yields_primitive_triples() {
+        for (a, b, c) in PrimitiveTriples::new(TripleBound::Hypotenuse(5_000), TreeOrder::DepthFirst) {
+            assert_eq!(a * a + b * b, c * c);
+            assert_eq!(gcd(gcd(a, b), c), 1);
+            assert!(a < b && b < c);
+        }
+    }
+
+    #[test]
+    fn perimeters_with_one_triple_known_values() {
+        // 12, 24, 30, 36, 40 and 48 have exactly one triple
+        assert_eq!(perimeters_with_one_triple(50), 6);
+        assert_eq!(perimeters_with_one_triple(1_500_000), 161_667);
+    }
+}
@@ -0,0 +1,78 @@
+// Every Pythagorean triple is a multiple of a primitive one:
+//
+//   (a, b, c) = k * (m^2 - n^2, 2mn, m^2 + n^2)
+//
+// with m > n >= 1, gcd(m, n) = 1 and m - n odd. Its sum is:
+//
+//   a + b + c = 2km(m + n)
+//
+// For a target sum S, with s2 = S / 2 and d = m + n:
+//   - m must divide s2; let q = s2 / m
+//   - d must divide q, and k = q / d
+//   - n = d - m, so 1 <= n < m  <=>  m < d < 2m
+//   - m - n odd  <=>  d odd
+//   - gcd(m, n) = 1  <=>  gcd(m, d) = 1
+
+use crate::triple;
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// Given q = s2 / m, try d = m + n for each candidate in (m, 2m) and derive
+// the scale k, enforcing the primitive-parameter invariants above.
+fn scaled_candidates(q: u64, m: u64) -> impl Iterator<Item = (u64, u64)> {
+    (m + 1..(2 * m).min(q + 1))
+        // Invariant: d must divide q
+        .filter(move |&d| q % d == 0)
+        // Invariant: d odd and coprime to m, so (m, n) is primitive
+        .filter(move |&d| d % 2 == 1 && gcd(m, d) == 1)
+        .map(move |d| (d - m, q / d))
+}
+
+// All (a, b, c) with a < b < c, a^2 + b^2 = c^2 and a + b + c = sum,
+// including non-primitive triples, ordered by a.
+pub fn triples_with_perimeter(sum: u64) -> Vec<(u64, u64, u64)> {
+    // Edge invariant: sum must be even (since sum = 2km(m+n))
+    if sum % 2 != 0 {
+        return Vec::new();
+    }
+    let s2 = sum / 2;
+
+    let mut found: Vec<(u64, u64, u64)> = (2..)
+        // Bound invariant: since n >= 1 and k >= 1, m(m+1) <= s2
+        .take_while(|&m| m * (m + 1) <= s2)
+        // Invariant: m must divide s2
+        .filter(|&m| s2 % m == 0)
+        .flat_map(|m| scaled_candidates(s2 / m, m).map(move |(n, k)| (m, n, k)))
+        .map(|(m, n, k)| {
+            let (a, b, c) = triple(m, n);
+            let (a, b) = (a.min(b), a.max(b));
+            debug_assert_eq!(k * (a + b + c), sum);
+            (k * a, k * b, k * c)
+        })
+        .collect();
+
+    found.sort_unstable();
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_perimeters() {
+        assert_eq!(triples_with_perimeter(12), vec![(3, 4, 5)]);
+        assert_eq!(triples_with_perimeter(1000), vec![(200, 375, 425)]);
+        assert_eq!(
+            triples_with_perimeter(120),
+            vec![(20, 48, 52), (24, 45, 51), (30, 40, 50)]
+        );
+        assert!(triples_with_perimeter(11).is_empty());
+        assert!(triples_with_perimeter(0).is_empty());
+    }
+}
@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+use euler009::{find_product, find_product_iter, triples_with_perimeter};
+
+const LIMIT: u64 = 5000;
+
+// Every triple with a + b + c <= LIMIT, by checking all (a, b) pairs
+fn brute_force() -> HashMap<u64, Vec<(u64, u64, u64)>> {
+    let mut by_sum: HashMap<u64, Vec<(u64, u64, u64)>> = HashMap::new();
+    for a in 1..LIMIT / 3 {
+        for b in a + 1..(LIMIT - a) / 2 {
+            let c2 = a * a + b * b;
+            let c = (c2 as f64).sqrt() as u64;
+            if c * c == c2 && a + b + c <= LIMIT {
+                by_sum.entry(a + b + c).or_default().push((a, b, c));
+            }
+        }
+    }
+    by_sum
+}
+
+#[test]
+fn finds_expected_answer() {
+    assert_eq!(find_product(1000), Some(31_875_000));
+    assert_eq!(find_product_iter(1000), Some(31_875_000));
+}
+
+#[test]
+fn matches_brute_force_up_to_limit() {
+    let expected = brute_force();
+    for sum in 1..=LIMIT {
+        let want = expected.get(&sum).cloned().unwrap_or_default();
+        assert_eq!(triples_with_perimeter(sum), want, "sum = {sum}");
+    }
+}
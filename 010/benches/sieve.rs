@@ -0,0 +1,60 @@
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use euler010::{OddSieve, PrimeSieve, WheelSieve};
+
+const LIMIT: u64 = 100_000_000;
+
+fn bench_segmented(c: &mut Criterion) {
+    c.bench_function("segmented", |b| {
+        b.iter(|| euler010::sum_primes_below_segmented(black_box(LIMIT)))
+    });
+}
+
+fn bench_parallel(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parallel");
+    let max = euler010::default_threads().get();
+    for threads in [1, 2, 4, 8, 16].into_iter().filter(|&t| t <= max) {
+        let n = NonZeroUsize::new(threads).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(threads), &n, |b, &n| {
+            b.iter(|| euler010::sum_primes_below_parallel(black_box(LIMIT), n))
+        });
+    }
+    group.finish();
+}
+
+fn bench_lucy(c: &mut Criterion) {
+    c.bench_function("lucy", |b| {
+        b.iter(|| euler010::sum_primes_below_lucy(black_box(LIMIT)))
+    });
+}
+
+fn bench_bit_sieves(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bit_sieve");
+    // A single 10^9 sieve takes seconds, so take fewer samples
+    group.sample_size(10);
+    for limit in [2_000_000, 100_000_000, 1_000_000_000] {
+        group.bench_with_input(BenchmarkId::new("odd", limit), &limit, |b, &limit| {
+            b.iter(|| OddSieve::new(black_box(limit)).sum())
+        });
+        group.bench_with_input(BenchmarkId::new("wheel", limit), &limit, |b, &limit| {
+            b.iter(|| WheelSieve::new(black_box(limit)).sum())
+        });
+    }
+    group.finish();
+}
+
+fn criterion_config() -> Criterion {
+    Criterion::default()
+        .sample_size(50)
+        .warm_up_time(Duration::from_secs(2))
+        .measurement_time(Duration::from_secs(10))
+}
+
+criterion_group!(
+    name = sieve;
+    config = criterion_config();
+    targets = bench_segmented, bench_parallel, bench_lucy, bench_bit_sieves
+);
+criterion_main!(sieve);
@@ -0,0 +1,124 @@
+mod lucy;
+mod parallel;
+mod query;
+mod segmented;
+mod table;
+mod wheel;
+
+pub use lucy::{sum_prime_powers_below, sum_primes_below_lucy, sum_primes_in_classes_below};
+pub use parallel::{default_threads, sum_primes_below_parallel};
+pub use query::Sieve;
+pub use segmented::sum_primes_below_segmented;
+pub use table::TableError;
+pub use wheel::WheelSieve;
+
+use bitvec::prelude::*;
+
+fn within_sieve_limit(limit: usize) -> impl Fn(&usize) -> bool {
+    move |&i| {
+        let p = 2 * i + 3;
+        p * p < limit
+    }
+}
+
+fn mark_multiples(bits: &mut BitVec, len: usize, i: usize) {
+    let p = 2 * i + 3;
+    let p2 = p * p;
+
+    let mut j = (p2 - 3) / 2;
+    while j < len {
+        bits.set(j, true);
+        j += p;
+    } 
+}
+
+fn index_to_prime(i: usize) -> usize {
+    2 * i + 3
+}
+
+// Odd-only sieve: bit i is set iff 2i + 3 is composite, for 2i + 3 < limit
+fn sieve_odd(limit: usize) -> BitVec {
+    let len = limit.saturating_sub(2) / 2;
+    let mut bits: BitVec = bitvec![0; len];
+
+    // Sieve-marking phase
+    // Mutates - one closure that owns &mut bits
+    (0..len)
+        .take_while(within_sieve_limit(limit))
+        .for_each(|i| {
+            if !bits[i] {
+                mark_multiples(&mut bits, len, i);
+            }
+        });
+
+    bits
+}
+
+// A sieve of the primes below a fixed limit
+pub trait PrimeSieve {
+    fn new(limit: usize) -> Self;
+    fn limit(&self) -> usize;
+    // In increasing order
+    fn primes(&self) -> impl Iterator<Item = u64> + '_;
+
+    fn count(&self) -> usize {
+        self.primes().count()
+    }
+
+    // u128, as every other sum in this crate, since a u64 wraps for limits
+    // past about 3e10
+    fn sum(&self) -> u128 {
+        self.primes().map(|p| p as u128).sum()
+    }
+}
+
+// The original odd-only sieve, kept as the baseline for the wheel
+pub struct OddSieve {
+    limit: usize,
+    bits: BitVec,
+}
+
+impl PrimeSieve for OddSieve {
+    fn new(limit: usize) -> Self {
+        Self { limit, bits: sieve_odd(limit) }
+    }
+
+    fn limit(&self) -> usize {
+        self.limit
+    }
+
+    fn primes(&self) -> impl Iterator<Item = u64> + '_ {
+        let two = (self.limit > 2).then_some(2);
+        two.into_iter()
+            .chain(self.bits.iter_zeros().map(|i| index_to_prime(i) as u64))
+    }
+
+    fn sum(&self) -> u128 {
+        if self.limit <= 2 {
+            return 0;
+        }
+        let bits = &self.bits;
+        let len = bits.len();
+
+        // Summation
+        // Pure
+        2_u128
+            + (0..len)
+                .filter(|&i| !bits[i])
+                .map(|i| index_to_prime(i) as u128)
+                .sum::<u128>()
+    }
+}
+
+// Every prime below limit, in increasing order
+pub fn primes_below(limit: usize) -> Vec<u64> {
+    WheelSieve::new(limit).primes().collect()
+}
+
+pub fn count_primes_below(limit: usize) -> usize {
+    WheelSieve::new(limit).count()
+}
+
+pub fn sum_primes_below(limit: usize) -> u128 {
+    WheelSieve::new(limit).sum()
+}
@@ -0,0 +1,236 @@
+// Lucy_Hedgehog's prime sums.
+//
+// S(v) starts as the sum of f(n) over 2..=v and, after sifting by every
+// prime p <= sqrt(x), equals the sum of f(p) over the primes p <= v. As in
+// 007's prime counting, only the values v = floor(x / i) are ever needed:
+//
+//   - small[v]  holds S(v)       for v <= r
+//   - large[i]  holds S(x / i)   for i <= r
+//
+// f must be completely multiplicative (here f(n) = n^k), so sifting by p
+// removes the numbers whose smallest prime factor is p:
+//
+//   S(v) -= f(p) * (S(v / p) - S(p - 1))   for every v >= p^2
+//
+// For sums over residue classes mod m, each v keeps one sum S_t(v) per
+// class t. Removing p * q moves q's class t to class p * t mod m:
+//
+//   S_{p t mod m}(v) -= p * (S_t(v / p) - S_t(p - 1))   for every t
+//
+// Time is O(m x^(3/4)) and memory O(m sqrt(x)).
+
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// S(k, j) * j! for j = 0..=k, from S(k, j) = j S(k - 1, j) + S(k - 1, j - 1)
+fn stirling_factorials(k: u32) -> Vec<u128> {
+    let mut row = vec![1_u128];
+    for _ in 0..k {
+        let mut next = vec![0_u128; row.len() + 1];
+        for (j, &s) in row.iter().enumerate() {
+            next[j] += j as u128 * s;
+            next[j + 1] += s;
+        }
+        row = next;
+    }
+    row.iter().enumerate().map(|(j, &s)| (1..=j as u128).product::<u128>() * s).collect()
+}
+
+// 1^k + 2^k + ... + v^k, or None if it overflows u128.
+//
+//   sum_{n=1}^{v} n^k = sum_j S(k, j) j! C(v + 1, j + 1)   for k >= 1
+//
+// Every term is non-negative, so the sum overflows iff some step does.
+fn power_sum(v: u64, k: u32, coeffs: &[u128]) -> Option<u128> {
+    if k == 0 {
+        return Some(v as u128);
+    }
+    let n = v as u128 + 1;
+    let mut binom = n;
+    let mut total = 0_u128;
+    for (j, &c) in coeffs.iter().enumerate().skip(1) {
+        // C(n, j + 1) = C(n, j) * (n - j) / (j + 1), dividing out the gcd
+        // first so the product never overflows when the result fits
+        let (num, den) = (n.saturating_sub(j as u128), j as u128 + 1);
+        let g = gcd(binom, den);
+        binom = (binom / g).checked_mul(num / (den / g))?;
+        total = total.checked_add(c.checked_mul(binom)?)?;
+    }
+    Some(total)
+}
+
+// Sum of the n in [1, v] with n = t (mod m)
+fn class_sum(v: u64, t: u64, m: u64) -> u128 {
+    let first = if t == 0 { m } else { t };
+    if v < first {
+        return 0;
+    }
+    let count = ((v - first) / m + 1) as u128;
+    // C(count, 2) without forming count * (count - 1), which can overflow
+    let pairs = if count % 2 == 0 { count / 2 * (count - 1) } else { (count - 1) / 2 * count };
+    count * first as u128 + m as u128 * pairs
+}
+
+struct Lucy {
+    x: u64,
+    r: usize,
+    // classes per value
+    m: usize,
+    small: Vec<u128>,
+    large: Vec<u128>,
+}
+
+impl Lucy {
+    // init(v, out) writes the starting S_t(v) for every class t into out
+    fn new<F>(x: u64, m: usize, mut init: F) -> Option<Self>
+    where
+        F: FnMut(u64, &mut [u128]) -> Option<()>,
+    {
+        let r = x.isqrt() as usize;
+        let mut small = vec![0_u128; (r + 1) * m];
+        let mut large = vec![0_u128; (r + 1) * m];
+        for v in 1..=r {
+            init(v as u64, &mut small[v * m..(v + 1) * m])?;
+        }
+        for i in 1..=r {
+            init(x / i as u64, &mut large[i * m..(i + 1) * m])?;
+        }
+        Some(Self { x, r, m, small, large })
+    }
+
+    fn sift<W: Fn(u64) -> u128>(&mut self, weight: W) {
+        let (x, r, m) = (self.x, self.r, self.m);
+        let mut delta = vec![0_u128; m];
+
+        for p in 2..=r {
+            // p is prime iff sifting so far left it in its class
+            let c = p % m;
+            if self.small[p * m + c] == self.small[(p - 1) * m + c] {
+                continue;
+            }
+            let w = weight(p as u64);
+            let sp = self.small[(p - 1) * m..p * m].to_vec();
+            let p2 = (p * p) as u64;
+
+            // Same order as 007: decreasing v, so S(v / p) is still last round's value
+            let imax = r.min((x / p2) as usize);
+            for i in 1..=imax {
+                let d = i * p;
+                // Invariant: d > r implies x / d <= r
+                let src = if d <= r {
+                    &self.large[d * m..(d + 1) * m]
+                } else {
+                    let v = (x / d as u64) as usize;
+                    &self.small[v * m..(v + 1) * m]
+                };
+                removed(&mut delta, src, &sp, p, w);
+                for (s, d) in self.large[i * m..(i + 1) * m].iter_mut().zip(&delta) {
+                    *s -= d;
+                }
+            }
+            for v in (p2 as usize..=r).rev() {
+                let q = v / p;
+                removed(&mut delta, &self.small[q * m..(q + 1) * m], &sp, p, w);
+                for (s, d) in self.small[v * m..(v + 1) * m].iter_mut().zip(&delta) {
+                    *s -= d;
+                }
+            }
+        }
+    }
+
+    // S_t(x) for every class t
+    fn totals(&self) -> &[u128] {
+        &self.large[self.m..2 * self.m]
+    }
+}
+
+// delta[p t mod m] = w * (src[t] - sp[t]): what sifting by p takes out of
+// each class
+fn removed(delta: &mut [u128], src: &[u128], sp: &[u128], p: usize, w: u128) {
+    let m = delta.len();
+    delta.fill(0);
+    for (t, (&s, &s0)) in src.iter().zip(sp).enumerate() {
+        delta[p * t % m] += w * (s - s0);
+    }
+}
+
+// Sum of p^k over the primes p < limit, or None if it overflows u128.
+pub fn sum_prime_powers_below(limit: u64, k: u32) -> Option<u128> {
+    if limit <= 2 {
+        return Some(0);
+    }
+    let coeffs = stirling_factorials(k);
+    let mut lucy = Lucy::new(limit - 1, 1, |v, out| {
+        // Leave out n = 1, which contributes 1^k = 1
+        out[0] = power_sum(v, k, &coeffs)? - 1;
+        Some(())
+    })?;
+    lucy.sift(|p| (p as u128).pow(k));
+    Some(lucy.totals()[0])
+}
+
+pub fn sum_primes_below_lucy(limit: u64) -> u128 {
+    sum_prime_powers_below(limit, 1).expect("sum of primes below any u64 fits in u128")
+}
+
+// Sums of the primes p < limit by residue class: out[t] is the sum of the
+// primes p with p = t (mod m).
+pub fn sum_primes_in_classes_below(limit: u64, m: u64) -> Vec<u128> {
+    assert!(m >= 1, "modulus must be >= 1");
+    if limit <= 2 {
+        return vec![0; m as usize];
+    }
+    let lucy = Lucy::new(limit - 1, m as usize, |v, out| {
+        for (t, s) in out.iter_mut().enumerate() {
+            *s = class_sum(v, t as u64, m);
+        }
+        // Leave out n = 1
+        out[(1 % m) as usize] -= 1;
+        Some(())
+    });
+    let mut lucy = lucy.expect("class sums below any u64 fit in u128");
+    lucy.sift(|p| p as u128);
+    lucy.totals().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn power_sums_match_direct_sums() {
+        for k in 0..6 {
+            let coeffs = stirling_factorials(k);
+            for v in 0..50_u64 {
+                let want: u128 = (1..=v as u128).map(|n| n.pow(k)).sum();
+                assert_eq!(power_sum(v, k, &coeffs), Some(want), "v = {v}, k = {k}");
+            }
+        }
+        assert_eq!(power_sum(u64::MAX, 2, &stirling_factorials(2)), None);
+    }
+
+    #[test]
+    fn class_sums_match_direct_sums() {
+        for m in 1..8 {
+            for t in 0..m {
+                for v in 0..40 {
+                    let want: u128 = (1..=v).filter(|n| n % m == t).map(|n| n as u128).sum();
+                    assert_eq!(class_sum(v, t, m), want, "v = {v}, t = {t}, m = {m}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn small_limits() {
+        let expected = [0, 0, 0, 2, 5, 5, 10, 10, 17, 17, 17, 17, 28];
+        for (limit, &sum) in expected.iter().enumerate() {
+            assert_eq!(sum_primes_below_lucy(limit as u64), sum, "limit = {limit}");
+        }
+    }
+}
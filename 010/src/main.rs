@@ -1,54 +1,87 @@
-use bitvec::prelude::*;
+use std::env;
+use std::error::Error;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 
-fn within_sieve_limit(limit: usize) -> impl Fn(&usize) -> bool {
-    move |&i| {
-        let p = 2 * i + 3;
-        p * p < limit
-    }
-}
+use euler010::{
+    default_threads, sum_primes_below, sum_primes_below_lucy, sum_primes_below_parallel, PrimeSieve, Sieve,
+};
 
-fn mark_multiples(bits: &mut BitVec, len: usize, i: usize) {
-    let p = 2 * i + 3;
-    let p2 = p * p;
+const USAGE: &str = "usage: euler010 [-t THREADS | --lucy | --table PATH] [LIMIT]";
 
-    let mut j = (p2 - 3) / 2;
-    while j < len {
-        bits.set(j, true);
-        j += p;
-    } 
+struct Args {
+    limit: Option<u64>,
+    // None means one per available core
+    threads: Option<NonZeroUsize>,
+    lucy: bool,
+    table: Option<PathBuf>,
 }
 
-fn index_to_prime(i: usize) -> usize {
-    2 * i + 3
+fn parse_args() -> Result<Args, Box<dyn Error>> {
+    let mut args = Args { limit: None, threads: None, lucy: false, table: None };
+    let mut it = env::args().skip(1);
+
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "-t" | "--threads" => {
+                let v = it.next().ok_or("missing value for --threads")?;
+                args.threads = Some(v.parse().map_err(|e| format!("Invalid thread count {v:?}: {e}"))?);
+            }
+            "-l" | "--lucy" => args.lucy = true,
+            "--table" => args.table = Some(it.next().ok_or("missing value for --table")?.into()),
+            "-h" | "--help" => return Err(USAGE.into()),
+            s if s.starts_with('-') => return Err(format!("Unknown flag {s:?}\n{USAGE}").into()),
+            _ => {
+                let limit = arg.parse().map_err(|e| format!("Invalid limit {arg:?}: {e}"))?;
+                args.limit = Some(limit);
+            }
+        }
+    }
+
+    // Each mode ignores the others' options, so a mix is a usage error
+    let modes = [args.threads.is_some(), args.lucy, args.table.is_some()];
+    if modes.iter().filter(|&&set| set).count() > 1 {
+        return Err(format!("--threads, --lucy and --table cannot be combined\n{USAGE}").into());
+    }
+    // Only a LIMIT selects the parallel or Lucy path
+    if (args.threads.is_some() || args.lucy) && args.limit.is_none() {
+        return Err(format!("--threads and --lucy need a LIMIT\n{USAGE}").into());
+    }
+    Ok(args)
 }
 
-fn sum_primes_below(limit: usize) -> u64 {
-    if limit <= 2 {
-        return 0;
+// Sum below limit from a saved table, building and saving it first if the
+// file does not exist yet
+fn sum_from_table(path: &Path, limit: Option<u64>) -> Result<u128, Box<dyn Error>> {
+    let sieve = if path.exists() {
+        Sieve::load(path).map_err(|e| format!("{}: {e}", path.display()))?
+    } else {
+        let limit = limit.ok_or("LIMIT is required to build a new table")?;
+        let sieve = Sieve::new(limit as usize);
+        sieve.save(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        sieve
+    };
+    let covered = sieve.limit() as u64;
+    let limit = limit.unwrap_or(covered);
+    if limit > covered {
+        return Err(format!("{} only covers primes below {covered}", path.display()).into());
     }
-    let len = (limit - 2) / 2;
-    let mut bits: BitVec = bitvec![0; len];
-
-    // Sieve-marking phase
-    // Mutates - one closure that owns &mut bits
-    (0..len)
-        .take_while(within_sieve_limit(limit))
-        .for_each(|i| {
-            if !bits[i] {
-                mark_multiples(&mut bits, len, i);
-            }
-        });
-
-    // Summation
-    // Pure
-    2_u64
-        + (0..len)
-            .filter(|&i| !bits[i])
-            .map(|i| index_to_prime(i) as u64)
-            .sum::<u64>()
+    Ok(sieve.sum_below(limit))
 }
 
-fn main() {
-    let result = sum_primes_below(2_000_000);
-    println!("{result}");
-}
\ No newline at end of file
+fn main() -> Result<(), Box<dyn Error>> {
+    let Args { limit, threads, lucy, table } = parse_args()?;
+    if let Some(path) = table {
+        println!("{}", sum_from_table(&path, limit)?);
+        return Ok(());
+    }
+    match limit {
+        Some(limit) if lucy => println!("{}", sum_primes_below_lucy(limit)),
+        Some(limit) => {
+            let threads = threads.unwrap_or_else(default_threads);
+            println!("{}", sum_primes_below_parallel(limit, threads))
+        }
+        None => println!("{}", sum_primes_below(2_000_000)),
+    }
+    Ok(())
+}
@@ -0,0 +1,43 @@
+// Multi-threaded segmented sieve.
+//
+// The blocks of the sequential segmented sieve are split into contiguous
+// runs, one per worker. Each worker works out where every base prime's
+// multiples start in its first block, then sieves and sums its run exactly
+// as the sequential path does. Partial sums are exact u128 values, so the
+// total does not depend on the thread count or on scheduling.
+
+use std::num::NonZeroUsize;
+use std::thread;
+
+use crate::segmented::{odd_base_primes, odd_count, sum_odd_range, SEGMENT_SPAN};
+
+// Number of worker threads to use when none is given
+pub fn default_threads() -> NonZeroUsize {
+    thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)
+}
+
+pub fn sum_primes_below_parallel(limit: u64, threads: NonZeroUsize) -> u128 {
+    if limit <= 2 {
+        return 0;
+    }
+    let base = odd_base_primes(limit);
+
+    // Invariant: every run starts on a block boundary of the sequential
+    // sieve, so the runs tile [3, limit) without overlap
+    let blocks = odd_count(3, limit).div_ceil(SEGMENT_SPAN);
+    let per_worker = blocks.div_ceil(threads.get() as u64).max(1);
+    let block_low = |b: u64| (3 + 2 * b * SEGMENT_SPAN).min(limit);
+
+    let partials: Vec<u128> = thread::scope(|s| {
+        let workers: Vec<_> = (0..blocks)
+            .step_by(per_worker as usize)
+            .map(|b| {
+                let (low, high, base) = (block_low(b), block_low(b + per_worker), &base);
+                s.spawn(move || sum_odd_range(low, high, base))
+            })
+            .collect();
+        workers.into_iter().map(|w| w.join().unwrap()).collect()
+    });
+
+    2 + partials.iter().sum::<u128>()
+}
@@ -0,0 +1,247 @@
+// Prime queries over a kept wheel sieve.
+//
+// Alongside the wheel bytes, every BLOCK bytes we store the number and sum
+// of the wheel primes in all the bytes before it:
+//
+//   counts[b] = primes >= 7 in bytes[..b * BLOCK]
+//   sums[b]   = their sum
+//
+// A prefix query then reads one table entry, then at most BLOCK bytes, then
+// one masked byte, so it is O(1). `nth` binary searches `counts` and is
+// O(log n).
+
+use std::io;
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
+
+use crate::table::TableError;
+use crate::wheel::{byte_primes, BIT_OF, NONE, RESIDUES};
+use crate::{PrimeSieve, WheelSieve};
+
+// Bytes per rank block: 1920 numbers
+const BLOCK: usize = 64;
+
+// BELOW[r] has the bits j with RESIDUES[j] < r
+const BELOW: [u8; 30] = {
+    let mut table = [0_u8; 30];
+    let mut r = 0;
+    while r < 30 {
+        let mut j = 0;
+        while j < 8 {
+            if RESIDUES[j] < r as u64 {
+                table[r] |= 1 << j;
+            }
+            j += 1;
+        }
+        r += 1;
+    }
+    table
+};
+
+// RESIDUE_SUM[b] is the sum of RESIDUES[j] over the bits j set in b
+const RESIDUE_SUM: [u8; 256] = {
+    let mut table = [0_u8; 256];
+    let mut b = 0;
+    while b < 256 {
+        let mut j = 0;
+        while j < 8 {
+            if b & (1 << j) != 0 {
+                table[b] += RESIDUES[j] as u8;
+            }
+            j += 1;
+        }
+        b += 1;
+    }
+    table
+};
+
+// Sum of the numbers in byte i whose bits are set in `byte`
+fn byte_sum(i: usize, byte: u8) -> u128 {
+    30 * i as u128 * byte.count_ones() as u128 + RESIDUE_SUM[byte as usize] as u128
+}
+
+pub struct Sieve {
+    wheel: WheelSieve,
+    counts: Vec<u64>,
+    sums: Vec<u128>,
+}
+
+impl Sieve {
+    // Builds the rank blocks over an existing wheel sieve
+    pub fn from_wheel(wheel: WheelSieve) -> Self {
+        let blocks = wheel.bytes().len() / BLOCK + 1;
+        let mut counts = Vec::with_capacity(blocks);
+        let mut sums = Vec::with_capacity(blocks);
+        let (mut count, mut sum) = (0_u64, 0_u128);
+        counts.push(count);
+        sums.push(sum);
+        for (b, chunk) in wheel.bytes().chunks_exact(BLOCK).enumerate() {
+            for (j, &byte) in chunk.iter().enumerate() {
+                count += byte.count_ones() as u64;
+                sum += byte_sum(b * BLOCK + j, byte);
+            }
+            counts.push(count);
+            sums.push(sum);
+        }
+        Self { wheel, counts, sums }
+    }
+
+    // Only the wheel bytes are stored; the rank blocks are rebuilt on load
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.wheel.save(path)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TableError> {
+        WheelSieve::load(path).map(Self::from_wheel)
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self.wheel.bytes()
+    }
+
+    // Byte i = n / 30 and the bits of byte i for numbers below n
+    fn split(&self, n: u64) -> (usize, u8) {
+        let i = (n / 30) as usize;
+        let byte = self.bytes().get(i).copied().unwrap_or(0);
+        (i, byte & BELOW[(n % 30) as usize])
+    }
+
+    // Resolves a range to [lo, hi) within the sieve. An empty or reversed
+    // range gives lo == hi, wherever its start is.
+    fn bounds(&self, range: impl RangeBounds<u64>) -> (u64, u64) {
+        let limit = self.limit() as u64;
+        let lo = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let hi = match range.end_bound() {
+            Bound::Included(&n) => n.saturating_add(1),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => limit,
+        };
+        assert!(hi <= limit, "range end {hi} is past the sieve limit {limit}");
+        (lo.min(hi), hi)
+    }
+
+    pub fn is_prime(&self, n: u64) -> bool {
+        assert!(n < self.limit() as u64, "{n} is not below the sieve limit {}", self.limit());
+        match BIT_OF[(n % 30) as usize] {
+            _ if n < 7 => matches!(n, 2 | 3 | 5),
+            NONE => false,
+            bit => self.bytes()[(n / 30) as usize] & (1 << bit) != 0,
+        }
+    }
+
+    // Number of primes p < n, for n up to the limit
+    pub fn count_below(&self, n: u64) -> u64 {
+        assert!(n <= self.limit() as u64, "{n} is past the sieve limit {}", self.limit());
+        let small = [2, 3, 5].iter().filter(|&&p| p < n).count() as u64;
+        let (i, partial) = self.split(n);
+        let block = i / BLOCK;
+        let between: u64 = self.bytes()[block * BLOCK..i].iter().map(|b| b.count_ones() as u64).sum();
+        small + self.counts[block] + between + partial.count_ones() as u64
+    }
+
+    // Sum of the primes p < n, for n up to the limit
+    pub fn sum_below(&self, n: u64) -> u128 {
+        assert!(n <= self.limit() as u64, "{n} is past the sieve limit {}", self.limit());
+        let small: u128 = [2, 3, 5].iter().filter(|&&p| p < n).sum::<u64>() as u128;
+        let (i, partial) = self.split(n);
+        let block = i / BLOCK;
+        let between: u128 = (block * BLOCK..i).map(|j| byte_sum(j, self.bytes()[j])).sum();
+        small + self.sums[block] + between + byte_sum(i, partial)
+    }
+
+    pub fn count_in(&self, range: impl RangeBounds<u64>) -> u64 {
+        let (lo, hi) = self.bounds(range);
+        self.count_below(hi) - self.count_below(lo)
+    }
+
+    pub fn sum_in(&self, range: impl RangeBounds<u64>) -> u128 {
+        let (lo, hi) = self.bounds(range);
+        self.sum_below(hi) - self.sum_below(lo)
+    }
+
+    // The kth prime, counting 2 as the first, if it is below the limit
+    pub fn nth(&self, k: u64) -> Option<u64> {
+        assert!(k >= 1, "k must be >= 1");
+        let small: Vec<u64> = self.wheel.wheel_primes().collect();
+        if k <= small.len() as u64 {
+            return Some(small[k as usize - 1]);
+        }
+        let k = k - small.len() as u64;
+
+        // Invariant: counts[block] < k, so the kth wheel prime is past the
+        // start of the block
+        let block = self.counts.partition_point(|&c| c < k) - 1;
+        let mut left = k - self.counts[block];
+        for (i, &byte) in self.bytes().iter().enumerate().skip(block * BLOCK) {
+            let ones = byte.count_ones() as u64;
+            if left <= ones {
+                return byte_primes(i, byte).nth(left as usize - 1);
+            }
+            left -= ones;
+        }
+        None
+    }
+
+    // The primes in the range, in increasing order
+    pub fn iter_range(&self, range: impl RangeBounds<u64>) -> impl Iterator<Item = u64> + '_ {
+        let (lo, hi) = self.bounds(range);
+        let small = [2, 3, 5].into_iter().filter(move |&p| lo <= p && p < hi);
+        let first = (lo / 30) as usize;
+        let last = (hi.div_ceil(30) as usize).min(self.bytes().len());
+        let wheel = (first..last)
+            .flat_map(move |i| byte_primes(i, self.bytes()[i]))
+            .skip_while(move |&p| p < lo)
+            .take_while(move |&p| p < hi);
+        small.chain(wheel)
+    }
+}
+
+impl PrimeSieve for Sieve {
+    fn new(limit: usize) -> Self {
+        Self::from_wheel(WheelSieve::new(limit))
+    }
+
+    fn limit(&self) -> usize {
+        self.wheel.limit()
+    }
+
+    fn primes(&self) -> impl Iterator<Item = u64> + '_ {
+        self.wheel.primes()
+    }
+
+    fn count(&self) -> usize {
+        self.count_below(self.limit() as u64) as usize
+    }
+
+    fn sum(&self) -> u128 {
+        self.sum_below(self.limit() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn below_masks() {
+        assert_eq!(BELOW[0], 0);
+        assert_eq!(BELOW[1], 0);
+        assert_eq!(BELOW[2], 1);
+        assert_eq!(BELOW[8], 0b11);
+        assert_eq!(BELOW[29], 0x7F);
+    }
+
+    #[test]
+    fn byte_sums_match_byte_primes() {
+        for i in [0, 1, 1000] {
+            for byte in 0..=255_u8 {
+                let want: u128 = byte_primes(i, byte).map(|p| p as u128).sum();
+                assert_eq!(byte_sum(i, byte), want, "i = {i}, byte = {byte:#010b}");
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+// Segmented odd-only sieve.
+//
+// Only the base primes below sqrt(limit) are kept for the whole run. The
+// odd numbers above them are sieved one block at a time, with bit i of a
+// block standing for low + 2i, so memory stays constant (one block plus the
+// base primes) no matter how large the limit is.
+
+use crate::primes_below;
+
+// 32 KiB of bits per block, sized for L1; 2^18 odd numbers
+pub(crate) const SEGMENT_WORDS: usize = 4 * 1024;
+pub(crate) const SEGMENT_SPAN: u64 = SEGMENT_WORDS as u64 * 64;
+
+// Odd base primes p with p * p < limit
+pub(crate) fn odd_base_primes(limit: u64) -> Vec<u64> {
+    let root = limit.saturating_sub(1).isqrt();
+    primes_below(root as usize + 1).into_iter().skip(1).collect()
+}
+
+// Marks the odd composites in [low, low + 2 * span) into `words`.
+//
+// Invariants:
+//   - low is odd and span <= SEGMENT_SPAN
+//   - next[j] is the first odd multiple of base[j] that is >= low and
+//     >= base[j]^2; on return it is the first one past this block
+pub(crate) fn sieve_block(low: u64, span: u64, words: &mut [u64], base: &[u64], next: &mut [u64]) {
+    let high = low + 2 * span;
+    let used = span.div_ceil(64) as usize;
+    words[..used].fill(0);
+
+    for (&p, m) in base.iter().zip(next.iter_mut()) {
+        if *m >= high {
+            continue;
+        }
+        // Walk block indices directly; odd multiples of p are p bits apart
+        let (p, span) = (p as usize, span as usize);
+        let mut i = ((*m - low) / 2) as usize;
+        while i < span {
+            words[i >> 6] |= 1 << (i & 63);
+            i += p;
+        }
+        *m = low + 2 * i as u64;
+    }
+
+    // Treat the unused tail of the last word as composite
+    if span % 64 != 0 {
+        words[used - 1] |= !0_u64 << (span % 64);
+    }
+}
+
+// Sum of the primes left unmarked in a block starting at low
+pub(crate) fn sum_block(low: u64, words: &[u64]) -> u128 {
+    let mut total = 0_u128;
+    for (w, &word) in words.iter().enumerate() {
+        let mut primes = !word;
+        while primes != 0 {
+            let i = w * 64 + primes.trailing_zeros() as usize;
+            total += (low + 2 * i as u64) as u128;
+            primes &= primes - 1;
+        }
+    }
+    total
+}
+
+// Odd numbers in [low, limit) when low is odd
+pub(crate) fn odd_count(low: u64, limit: u64) -> u64 {
+    limit.saturating_sub(low).div_ceil(2)
+}
+
+// First odd multiple of each base prime that is >= low and >= p^2
+pub(crate) fn first_multiples(low: u64, base: &[u64]) -> Vec<u64> {
+    base.iter()
+        .map(|&p| {
+            let m = (p * p).max(low.div_ceil(p) * p);
+            if m % 2 == 0 { m + p } else { m }
+        })
+        .collect()
+}
+
+// Sum of the primes among the odd numbers in [low, high), low odd.
+// `base` must hold every odd prime p with p * p < high.
+pub(crate) fn sum_odd_range(low: u64, high: u64, base: &[u64]) -> u128 {
+    let mut next = first_multiples(low, base);
+    let mut words = vec![0_u64; SEGMENT_WORDS];
+
+    let mut total = 0_u128;
+    let mut low = low;
+    while low < high {
+        let span = odd_count(low, high).min(SEGMENT_SPAN);
+        sieve_block(low, span, &mut words, base, &mut next);
+        total += sum_block(low, &words[..span.div_ceil(64) as usize]);
+        low += 2 * span;
+    }
+    total
+}
+
+pub fn sum_primes_below_segmented(limit: u64) -> u128 {
+    if limit <= 2 {
+        return 0;
+    }
+    2 + sum_odd_range(3, limit, &odd_base_primes(limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_multiples_are_odd_and_in_range() {
+        let base = [3, 5, 7, 11, 13];
+        for low in [3, 9, 25, 101, 1_000_001] {
+            for (&p, &m) in base.iter().zip(&first_multiples(low, &base)) {
+                assert!(m % 2 == 1 && m % p == 0, "p = {p}, m = {m}");
+                assert!(m >= low.max(p * p) && m < low.max(p * p) + 2 * p, "p = {p}, low = {low}");
+            }
+        }
+    }
+
+    #[test]
+    fn small_limits() {
+        let expected = [0, 0, 0, 2, 5, 5, 10, 10, 17, 17, 17, 17, 28];
+        for (limit, &sum) in expected.iter().enumerate() {
+            assert_eq!(sum_primes_below_segmented(limit as u64), sum, "limit = {limit}");
+        }
+    }
+}
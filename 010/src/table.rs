@@ -0,0 +1,237 @@
+// On-disk wheel sieve tables.
+//
+// A table is a fixed header followed by the wheel bytes as they are held
+// in memory. All integers are little-endian:
+//
+//   offset  size  field
+//        0     8  magic "E010PRIM"
+//        8     4  format version
+//       12     4  wheel modulus (30)
+//       16     8  wheel residues, one byte each
+//       24     8  limit
+//       32     8  payload length in bytes
+//       40     4  CRC-32 of bytes 0..40 and the payload
+//       44     4  reserved, zero
+//       48        payload
+//
+// Loading maps the file instead of reading it, so a large table costs no
+// more than its header until bytes are touched. The checksum is still
+// verified up front, which reads the whole file once. Saving replaces the
+// file by renaming a new one over it, so existing maps keep the old bytes.
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::ops::Deref;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::wheel::RESIDUES;
+
+const MAGIC: &[u8; 8] = b"E010PRIM";
+const VERSION: u32 = 1;
+const MODULUS: u32 = 30;
+const CHECKSUM_AT: usize = 40;
+pub(crate) const HEADER_LEN: usize = 48;
+
+#[derive(Debug)]
+pub enum TableError {
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u32),
+    // The file was written for a different wheel
+    WrongWheel,
+    // The payload length does not match the limit or the file size
+    Truncated { expected: u64, found: u64 },
+    ChecksumMismatch { expected: u32, found: u32 },
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableError::Io(e) => write!(f, "failed to access table: {e}"),
+            TableError::BadMagic => write!(f, "not a prime table"),
+            TableError::UnsupportedVersion(v) => write!(f, "unsupported table version {v}"),
+            TableError::WrongWheel => write!(f, "table uses a different wheel layout"),
+            TableError::Truncated { expected, found } => {
+                write!(f, "table payload is {found} bytes, expected {expected}")
+            }
+            TableError::ChecksumMismatch { expected, found } => {
+                write!(f, "table checksum is {found:#010x}, expected {expected:#010x}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+impl From<io::Error> for TableError {
+    fn from(e: io::Error) -> Self {
+        TableError::Io(e)
+    }
+}
+
+// Wheel bytes, either sieved in memory or mapped from a table file
+pub(crate) enum Storage {
+    Owned(Vec<u8>),
+    // The whole file; the payload starts at HEADER_LEN
+    Mapped(Mmap),
+}
+
+impl Deref for Storage {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Storage::Owned(bytes) => bytes,
+            Storage::Mapped(map) => &map[HEADER_LEN..],
+        }
+    }
+}
+
+fn header_prefix(limit: u64, len: u64) -> [u8; CHECKSUM_AT] {
+    let mut head = [0_u8; CHECKSUM_AT];
+    head[0..8].copy_from_slice(MAGIC);
+    head[8..12].copy_from_slice(&VERSION.to_le_bytes());
+    head[12..16].copy_from_slice(&MODULUS.to_le_bytes());
+    for (b, &r) in head[16..24].iter_mut().zip(&RESIDUES) {
+        *b = r as u8;
+    }
+    head[24..32].copy_from_slice(&limit.to_le_bytes());
+    head[32..40].copy_from_slice(&len.to_le_bytes());
+    head
+}
+
+fn checksum(head: &[u8], payload: &[u8]) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(head);
+    hasher.update(payload);
+    hasher.finalize()
+}
+
+pub(crate) fn write_table<W: Write>(mut out: W, limit: u64, payload: &[u8]) -> io::Result<()> {
+    let head = header_prefix(limit, payload.len() as u64);
+    out.write_all(&head)?;
+    out.write_all(&checksum(&head, payload).to_le_bytes())?;
+    out.write_all(&[0; HEADER_LEN - CHECKSUM_AT - 4])?;
+    out.write_all(payload)?;
+    out.flush()
+}
+
+// Writes a sibling temp file and renames it over `path`. The old file is
+// never truncated in place, so a sieve that still maps it stays valid and
+// a failed write leaves the previous table intact.
+pub(crate) fn save_table(path: &Path, limit: u64, payload: &[u8]) -> io::Result<()> {
+    let mut name = path.file_name().ok_or(io::ErrorKind::InvalidInput)?.to_os_string();
+    name.push(format!(".tmp{}", std::process::id()));
+    let temp = path.with_file_name(name);
+
+    let written = File::create(&temp).and_then(|file| {
+        let mut out = BufWriter::new(file);
+        write_table(&mut out, limit, payload)?;
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        fs::rename(&temp, path)
+    });
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    written
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+}
+
+// Checks the header and checksum of a whole table file, returning the limit
+pub(crate) fn validate(file: &[u8]) -> Result<u64, TableError> {
+    if file.len() < HEADER_LEN {
+        return Err(if file.starts_with(MAGIC) || MAGIC.starts_with(file) {
+            TableError::Truncated { expected: HEADER_LEN as u64, found: file.len() as u64 }
+        } else {
+            TableError::BadMagic
+        });
+    }
+    if &file[0..8] != MAGIC {
+        return Err(TableError::BadMagic);
+    }
+    let version = read_u32(file, 8);
+    if version != VERSION {
+        return Err(TableError::UnsupportedVersion(version));
+    }
+    let residues_match = file[16..24].iter().zip(&RESIDUES).all(|(&b, &r)| b as u64 == r);
+    if read_u32(file, 12) != MODULUS || !residues_match {
+        return Err(TableError::WrongWheel);
+    }
+
+    let (limit, len) = (read_u64(file, 24), read_u64(file, 32));
+    // Invariant: one byte per 30 numbers below the limit
+    let expected = limit.div_ceil(MODULUS as u64);
+    let found = (file.len() - HEADER_LEN) as u64;
+    if len != expected || found != expected {
+        return Err(TableError::Truncated { expected, found });
+    }
+
+    let expected = read_u32(file, CHECKSUM_AT);
+    let found = checksum(&file[..CHECKSUM_AT], &file[HEADER_LEN..]);
+    if found != expected {
+        return Err(TableError::ChecksumMismatch { expected, found });
+    }
+    Ok(limit)
+}
+
+pub(crate) fn load_table(path: &Path) -> Result<(u64, Storage), TableError> {
+    let file = File::open(path)?;
+    // SAFETY: the map is read-only. Like any mapped file, it must not be
+    // truncated or rewritten by another process while the sieve is alive.
+    let map = unsafe { Mmap::map(&file)? };
+    let limit = validate(&map)?;
+    Ok((limit, Storage::Mapped(map)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(limit: u64, payload: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_table(&mut out, limit, payload).unwrap();
+        out
+    }
+
+    #[test]
+    fn round_trips_header() {
+        let file = table(61, &[0xFE, 0xFF, 0x7F]);
+        assert_eq!(file.len(), HEADER_LEN + 3);
+        assert_eq!(validate(&file).unwrap(), 61);
+    }
+
+    #[test]
+    fn detects_corruption() {
+        let file = table(61, &[0xFE, 0xFF, 0x7F]);
+
+        let mut flipped = file.clone();
+        flipped[HEADER_LEN + 1] ^= 0x10;
+        assert!(matches!(validate(&flipped), Err(TableError::ChecksumMismatch { .. })));
+
+        let mut limit = file.clone();
+        limit[24] = 62;
+        assert!(matches!(validate(&limit), Err(TableError::ChecksumMismatch { .. })));
+
+        let mut version = file.clone();
+        version[8] = 2;
+        assert!(matches!(validate(&version), Err(TableError::UnsupportedVersion(2))));
+
+        let mut wheel = file.clone();
+        wheel[17] = 5;
+        assert!(matches!(validate(&wheel), Err(TableError::WrongWheel)));
+
+        assert!(matches!(validate(&file[..file.len() - 1]), Err(TableError::Truncated { .. })));
+        assert!(matches!(validate(&file[..20]), Err(TableError::Truncated { .. })));
+        assert!(matches!(validate(b"not a table at all, just some text here.........."), Err(TableError::BadMagic)));
+    }
+}
@@ -0,0 +1,73 @@
+use euler010::{
+    sum_prime_powers_below, sum_primes_below_lucy, sum_primes_in_classes_below, OddSieve, PrimeSieve,
+};
+
+// The odd-only bit sieve, independent of the wheel behind primes_below
+fn odd_sieve_primes(limit: u64) -> Vec<u64> {
+    OddSieve::new(limit as usize).primes().collect()
+}
+
+fn classes_by_sieve(limit: u64, m: u64) -> Vec<u128> {
+    let mut sums = vec![0_u128; m as usize];
+    for p in odd_sieve_primes(limit) {
+        sums[(p % m) as usize] += p as u128;
+    }
+    sums
+}
+
+#[test]
+fn finds_expected_answer() {
+    assert_eq!(sum_primes_below_lucy(2_000_000), 142_913_828_922);
+}
+
+#[test]
+fn matches_bit_sieve() {
+    let limits = (0..300).chain([1_000, 65_536, 65_537, 999_983, 1_000_000]);
+    for limit in limits {
+        assert_eq!(sum_primes_below_lucy(limit), OddSieve::new(limit as usize).sum(), "limit = {limit}");
+    }
+}
+
+#[test]
+fn prime_powers_match_bit_sieve() {
+    for limit in [0, 3, 10, 1_000, 123_457] {
+        let primes = odd_sieve_primes(limit);
+        for k in 0..=4 {
+            let want: u128 = primes.iter().map(|&p| (p as u128).pow(k)).sum();
+            assert_eq!(sum_prime_powers_below(limit, k), Some(want), "limit = {limit}, k = {k}");
+        }
+    }
+    // pi(10^6)
+    assert_eq!(sum_prime_powers_below(1_000_000, 0), Some(78_498));
+    // The sum of p^4 below 2^40 is well past u128
+    assert_eq!(sum_prime_powers_below(1 << 40, 4), None);
+}
+
+#[test]
+fn classes_match_bit_sieve() {
+    for m in [1, 2, 3, 4, 6, 10, 12, 30] {
+        for limit in [0, 2, 3, 50, 10_007, 300_000] {
+            let sums = sum_primes_in_classes_below(limit, m);
+            assert_eq!(sums, classes_by_sieve(limit, m), "limit = {limit}, m = {m}");
+            assert_eq!(sums.iter().sum::<u128>(), sum_primes_below_lucy(limit));
+        }
+    }
+}
+
+// Run with: cargo test --release -- --ignored
+#[test]
+#[ignore]
+fn matches_bit_sieve_large() {
+    for limit in [10_000_000, 12_345_678, 100_000_000] {
+        assert_eq!(sum_primes_below_lucy(limit), OddSieve::new(limit as usize).sum(), "limit = {limit}");
+        for m in [4, 10] {
+            assert_eq!(sum_primes_in_classes_below(limit, m), classes_by_sieve(limit, m), "limit = {limit}, m = {m}");
+        }
+        let primes = odd_sieve_primes(limit);
+        let squares: u128 = primes.iter().map(|&p| (p as u128).pow(2)).sum();
+        assert_eq!(sum_prime_powers_below(limit, 2), Some(squares), "limit = {limit}");
+    }
+    assert_eq!(sum_primes_below_lucy(1_000_000_000), 24_739_512_092_254_535);
+    assert_eq!(sum_primes_below_lucy(10_000_000_000), 2_220_822_432_581_729_238);
+    assert_eq!(sum_primes_below_lucy(10_000_000_000_000), 1_699_246_443_377_779_418_889_494);
+}
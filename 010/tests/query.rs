@@ -0,0 +1,78 @@
+use euler010::{primes_below, PrimeSieve, Sieve};
+
+const LIMIT: u64 = 200_003;
+
+fn sample(limit: u64) -> impl Iterator<Item = u64> {
+    // Every n near the start, then a stride that hits every residue mod 30
+    (0..200).chain((200..=limit).step_by(97)).chain([limit])
+}
+
+#[test]
+fn prefix_queries_match_direct_scan() {
+    let sieve = Sieve::new(LIMIT as usize);
+    let primes = primes_below(LIMIT as usize);
+    for n in sample(LIMIT) {
+        let below = &primes[..primes.partition_point(|&p| p < n)];
+        assert_eq!(sieve.count_below(n), below.len() as u64, "n = {n}");
+        assert_eq!(sieve.sum_below(n), below.iter().map(|&p| p as u128).sum(), "n = {n}");
+        if n < LIMIT {
+            assert_eq!(sieve.is_prime(n), primes.binary_search(&n).is_ok(), "n = {n}");
+        }
+    }
+    assert_eq!(sieve.count(), primes.len());
+}
+
+#[test]
+fn nth_matches_direct_scan() {
+    let sieve = Sieve::new(LIMIT as usize);
+    let primes = primes_below(LIMIT as usize);
+    for (k, &p) in primes.iter().enumerate() {
+        assert_eq!(sieve.nth(k as u64 + 1), Some(p), "k = {}", k + 1);
+    }
+    assert_eq!(sieve.nth(primes.len() as u64 + 1), None);
+    assert_eq!(Sieve::new(4).nth(3), None);
+}
+
+#[test]
+fn range_queries_match_direct_scan() {
+    let sieve = Sieve::new(LIMIT as usize);
+    let primes = primes_below(LIMIT as usize);
+    let ends: Vec<u64> = sample(LIMIT).step_by(7).collect();
+    for &lo in &ends {
+        for &hi in ends.iter().filter(|&&hi| hi >= lo).step_by(13) {
+            let want = &primes[primes.partition_point(|&p| p < lo)..primes.partition_point(|&p| p < hi)];
+            assert_eq!(sieve.iter_range(lo..hi).collect::<Vec<_>>(), want, "{lo}..{hi}");
+            assert_eq!(sieve.count_in(lo..hi), want.len() as u64, "{lo}..{hi}");
+            assert_eq!(sieve.sum_in(lo..hi), want.iter().map(|&p| p as u128).sum(), "{lo}..{hi}");
+        }
+    }
+    assert_eq!(sieve.iter_range(..12).collect::<Vec<_>>(), vec![2, 3, 5, 7, 11]);
+    assert_eq!(sieve.iter_range(5..=13).collect::<Vec<_>>(), vec![5, 7, 11, 13]);
+    assert_eq!(sieve.sum_in(..), sieve.sum_below(LIMIT));
+    // Reversed ranges are empty, even when they start past the limit
+    for (lo, hi) in [(20, 10), (LIMIT, 0), (LIMIT + 5, 3)] {
+        assert_eq!(sieve.count_in(lo..hi), 0, "{lo}..{hi}");
+        assert_eq!(sieve.sum_in(lo..hi), 0, "{lo}..{hi}");
+        assert_eq!(sieve.iter_range(lo..hi).count(), 0, "{lo}..{hi}");
+    }
+}
+
+#[test]
+fn finds_expected_answer() {
+    let sieve = Sieve::new(2_000_000);
+    assert_eq!(sieve.sum(), 142_913_828_922);
+    assert_eq!(sieve.nth(10_001), Some(104_743));
+    assert_eq!(sieve.count_below(1_000_000), 78_498);
+}
+
+#[test]
+#[should_panic(expected = "past the sieve limit")]
+fn queries_past_the_limit_panic() {
+    Sieve::new(100).count_below(101);
+}
+
+#[test]
+#[should_panic(expected = "past the sieve limit")]
+fn ranges_to_u64_max_panic_without_overflow() {
+    Sieve::new(100).count_in(..=u64::MAX);
+}
@@ -0,0 +1,56 @@
+use std::num::NonZeroUsize;
+
+use euler010::{
+    sum_primes_below, sum_primes_below_parallel, sum_primes_below_segmented, OddSieve, PrimeSieve,
+};
+
+#[test]
+fn finds_expected_answer() {
+    assert_eq!(sum_primes_below(10), 17);
+    assert_eq!(sum_primes_below(2_000_000), 142_913_828_922);
+    assert_eq!(sum_primes_below_segmented(2_000_000), 142_913_828_922);
+}
+
+#[test]
+fn segmented_matches_bit_sieve() {
+    // Limits around the block boundary of 2^18 odd numbers
+    let block = 2 * (1_u64 << 18);
+    let limits = (0..200).chain([1_000, 65_537, 999_983, block, block + 1, block + 3, 5_000_000]);
+    for limit in limits {
+        assert_eq!(
+            sum_primes_below_segmented(limit),
+            OddSieve::new(limit as usize).sum(),
+            "limit = {limit}"
+        );
+    }
+}
+
+#[test]
+fn parallel_matches_segmented() {
+    // Includes more threads than blocks, and runs of uneven length
+    let block = 2 * (1_u64 << 18);
+    let limits = (0..50).chain([1_000, block, block + 1, 3 * block + 7, 5_000_000]);
+    for limit in limits {
+        let want = sum_primes_below_segmented(limit);
+        for threads in [1, 2, 3, 4, 7, 16] {
+            let threads = NonZeroUsize::new(threads).unwrap();
+            assert_eq!(sum_primes_below_parallel(limit, threads), want, "limit = {limit}, threads = {threads}");
+        }
+    }
+}
+
+// Run with: cargo test --release -- --ignored
+#[test]
+#[ignore]
+fn segmented_matches_bit_sieve_large() {
+    for limit in [10_000_000, 12_345_678, 100_000_000] {
+        assert_eq!(
+            sum_primes_below_segmented(limit),
+            OddSieve::new(limit as usize).sum(),
+            "limit = {limit}"
+        );
+    }
+    assert_eq!(sum_primes_below_segmented(1_000_000_000), 24_739_512_092_254_535);
+    let threads = NonZeroUsize::new(4).unwrap();
+    assert_eq!(sum_primes_below_parallel(1_000_000_000, threads), 24_739_512_092_254_535);
+}
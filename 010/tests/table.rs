@@ -0,0 +1,86 @@
+use std::fs;
+use std::path::PathBuf;
+
+use euler010::{PrimeSieve, Sieve, TableError, WheelSieve};
+
+// A fresh path per test, removed when dropped
+struct TempPath(PathBuf);
+
+impl TempPath {
+    fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("euler010-{}-{name}.primes", std::process::id()));
+        Self(path)
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn loaded_sieve_matches_fresh_sieve() {
+    let path = TempPath::new("round-trip");
+    for limit in [0, 1, 7, 30, 31, 1_000, 2_000_000] {
+        let fresh = Sieve::new(limit);
+        fresh.save(&path.0).unwrap();
+        let loaded = Sieve::load(&path.0).unwrap();
+        assert_eq!(loaded.limit(), limit);
+        assert!(loaded.primes().eq(fresh.primes()), "limit = {limit}");
+        assert_eq!(loaded.count(), fresh.count(), "limit = {limit}");
+        assert_eq!(loaded.sum(), fresh.sum(), "limit = {limit}");
+    }
+    let loaded = Sieve::load(&path.0).unwrap();
+    assert_eq!(loaded.sum(), 142_913_828_922);
+    assert_eq!(loaded.nth(10_001), Some(104_743));
+}
+
+#[test]
+fn corrupt_tables_are_rejected() {
+    let path = TempPath::new("corrupt");
+    WheelSieve::new(100_000).save(&path.0).unwrap();
+    let good = fs::read(&path.0).unwrap();
+
+    let mut flipped = good.clone();
+    *flipped.last_mut().unwrap() ^= 1;
+    fs::write(&path.0, &flipped).unwrap();
+    assert!(matches!(WheelSieve::load(&path.0), Err(TableError::ChecksumMismatch { .. })));
+
+    fs::write(&path.0, &good[..good.len() / 2]).unwrap();
+    assert!(matches!(WheelSieve::load(&path.0), Err(TableError::Truncated { .. })));
+
+    fs::write(&path.0, b"").unwrap();
+    assert!(matches!(WheelSieve::load(&path.0), Err(TableError::Truncated { .. })));
+
+    fs::write(&path.0, [&b"PRIMESv1"[..], &good[8..]].concat()).unwrap();
+    assert!(matches!(WheelSieve::load(&path.0), Err(TableError::BadMagic)));
+
+    fs::remove_file(&path.0).unwrap();
+    assert!(matches!(WheelSieve::load(&path.0), Err(TableError::Io(_))));
+}
+
+#[test]
+fn saving_over_a_mapped_table() {
+    let path = TempPath::new("resave");
+    Sieve::new(100_000).save(&path.0).unwrap();
+    let loaded = Sieve::load(&path.0).unwrap();
+    let want = loaded.sum_below(100_000);
+
+    // Writing back to the file it maps, or a different sieve over it, must
+    // leave the mapped bytes alone
+    loaded.save(&path.0).unwrap();
+    assert_eq!(loaded.sum_below(100_000), want);
+    Sieve::new(50_000).save(&path.0).unwrap();
+    assert_eq!(loaded.sum_below(100_000), want);
+    assert_eq!(loaded.nth(9_592), Some(99_991));
+
+    let reloaded = Sieve::load(&path.0).unwrap();
+    assert_eq!(reloaded.limit(), 50_000);
+    let leftovers = fs::read_dir(path.0.parent().unwrap())
+        .unwrap()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_name().to_string_lossy().starts_with(&*path.0.file_name().unwrap().to_string_lossy()))
+        .count();
+    assert_eq!(leftovers, 1, "temp files left next to the table");
+}
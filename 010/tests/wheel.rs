@@ -0,0 +1,33 @@
+use euler010::{OddSieve, PrimeSieve, WheelSieve};
+
+fn assert_same(limit: usize) {
+    let (odd, wheel) = (OddSieve::new(limit), WheelSieve::new(limit));
+    assert!(odd.primes().eq(wheel.primes()), "limit = {limit}");
+    assert_eq!(odd.count(), wheel.count(), "limit = {limit}");
+    assert_eq!(odd.sum(), wheel.sum(), "limit = {limit}");
+}
+
+#[test]
+fn wheel_matches_odd_sieve() {
+    // Limits on and around byte boundaries of both layouts
+    let limits = (0..400).chain([899, 900, 901, 961, 1_000, 65_537, 999_983, 2_000_000]);
+    for limit in limits {
+        assert_same(limit);
+    }
+}
+
+#[test]
+fn known_counts() {
+    assert_eq!(WheelSieve::new(1_000_000).count(), 78_498);
+    assert_eq!(WheelSieve::new(2_000_000).sum(), 142_913_828_922);
+}
+
+// Run with: cargo test --release -- --ignored
+#[test]
+#[ignore]
+fn wheel_matches_odd_sieve_large() {
+    for limit in [10_000_000, 12_345_679, 100_000_000] {
+        assert_same(limit);
+    }
+    assert_eq!(WheelSieve::new(1_000_000_000).count(), 50_847_534);
+}
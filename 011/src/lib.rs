@@ -0,0 +1,234 @@
+mod lines;
+mod ndgrid;
+mod parse;
+mod paths;
+mod run;
+mod search;
+
+pub use ndgrid::{NdGrid, NdRun, ProductOverflow, ShapeError};
+pub use parse::{GridFormat, GridParseError};
+pub use paths::{Path, Rect};
+pub use run::Run;
+pub use search::RunSearch;
+
+use std::io::Read;
+
+use num_bigint::BigInt;
+
+#[cfg(test)]
+use lines::RunProduct;
+
+// (product, start, direction) of a run before its values are collected
+#[cfg(test)]
+type Candidate<P = i64> = (P, (usize, usize), (isize, isize));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    cells: Vec<i64>,
+    rows: usize,
+    cols: usize,
+}
+
+impl Grid {
+    pub const DIRS: [(isize, isize); 4] = [(0,1), (1,0), (1,1), (1,-1)];
+    // Every king move, so each straight run is also found backwards
+    pub const ALL_DIRS: [(isize, isize); 8] = [(0,1), (1,0), (1,1), (1,-1), (0,-1), (-1,0), (-1,-1), (-1,1)];
+    pub const KNIGHT_DIRS: [(isize, isize); 8] = [(1,2), (2,1), (2,-1), (1,-2), (-1,-2), (-2,-1), (-2,1), (-1,2)];
+
+    // Whitespace-separated rows, one per line
+    pub fn try_from_str(s: &str) -> Result<Self, GridParseError> {
+        Self::parse(s, GridFormat::Whitespace)
+    }
+
+    pub fn parse(s: &str, format: GridFormat) -> Result<Self, GridParseError> {
+        let (cells, rows, cols) = parse::parse(s, format)?;
+        Ok(Self { cells, rows, cols })
+    }
+
+    pub fn read<R: Read>(mut input: R, format: GridFormat) -> Result<Self, GridParseError> {
+        let mut s = String::new();
+        input.read_to_string(&mut s)?;
+        Self::parse(&s, format)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn at(&self, r: usize, c: usize) -> i64 {
+        self.cells[r * self.cols + c]
+    }
+
+    // (row, column) of the kth cell of a run; with wrap, the grid is a torus
+    fn cell(&self, (r, c): (usize, usize), (dr, dc): (isize, isize), k: usize, wrap: bool) -> (usize, usize) {
+        let rr = r as isize + dr * (k as isize);
+        let cc = c as isize + dc * (k as isize);
+        if wrap {
+            (rr.rem_euclid(self.rows as isize) as usize, cc.rem_euclid(self.cols as isize) as usize)
+        } else {
+            (rr as usize, cc as usize)
+        }
+    }
+
+    // Reference scan: recomputes every run from scratch. The tests check
+    // the line walk against it.
+    #[cfg(test)]
+    fn prod_run(&self, start: (usize, usize), dir: (isize, isize), len: usize, wrap: bool) -> i64 {
+        (0..len).fold(1i64, |prod, k| {
+            let (rr, cc) = self.cell(start, dir, k, wrap);
+            prod * self.at(rr, cc)
+        })
+    }
+
+    #[cfg(test)]
+    fn scan_dir(&self, dir: (isize, isize), len: usize, wrap: bool) -> Vec<Candidate> {
+        // Invariant: a start is valid iff every cell of its run is inside
+        // the grid, which bounds rows and columns independently
+        let rows = search::axis_starts(self.rows, dir.0, len, wrap);
+        let cols = search::axis_starts(self.cols, dir.1, len, wrap);
+        rows.flat_map(|r| cols.clone().map(move |c| (r, c)))
+            .map(|start| (self.prod_run(start, dir, len, wrap), start, dir))
+            .collect()
+    }
+
+    // The search directions as NdGrid steps
+    fn nd_dirs(search: &RunSearch) -> Vec<Vec<isize>> {
+        search.dirs().iter().map(|&(dr, dc)| vec![dr, dc]).collect()
+    }
+
+    // Every run of `len` cells, in direction order and then row-major by
+    // start. Only the tests need them all.
+    #[cfg(test)]
+    fn candidates<P: RunProduct>(&self, len: usize, search: &RunSearch) -> Vec<Candidate<P>> {
+        let nd = NdGrid::from(self);
+        let found = nd.candidates::<P>(len, &Self::nd_dirs(search), search.wraps());
+        found.into_iter().map(|(product, start, d)| (product, self.coords(start), search.dirs()[d])).collect()
+    }
+
+    fn coords(&self, index: usize) -> (usize, usize) {
+        (index / self.cols, index % self.cols)
+    }
+
+    fn to_run<P>(&self, (product, start, d): ndgrid::Candidate<P>, len: usize, search: &RunSearch) -> Run<P> {
+        let (start, dir) = (self.coords(start), search.dirs()[d]);
+        let cells: Vec<(usize, usize)> = (0..len).map(|k| self.cell(start, dir, k, search.wraps())).collect();
+        let values = cells.iter().map(|&(r, c)| self.at(r, c)).collect();
+        Run::new(product, start, dir, values, cells)
+    }
+
+    // The search itself is NdGrid's, over a 2D view of this grid
+    fn ranked_exact(&self, len: usize, k: usize, search: &RunSearch) -> Vec<Run<BigInt>> {
+        let best = NdGrid::from(self).ranked_exact(len, k, &Self::nd_dirs(search), search.wraps());
+        best.into_iter().map(|cand| self.to_run(cand, len, search)).collect()
+    }
+
+    fn ranked(&self, len: usize, k: usize, search: &RunSearch) -> Result<Vec<Run>, ProductOverflow> {
+        let best = NdGrid::from(self).ranked(len, k, &Self::nd_dirs(search), search.wraps())?;
+        Ok(best.into_iter().map(|cand| self.to_run(cand, len, search)).collect())
+    }
+
+    // The run with the largest product; ties go to the first in scan order
+    pub fn max_run(&self, len: usize) -> Result<Option<Run>, ProductOverflow> {
+        self.max_run_with(len, &RunSearch::default())
+    }
+
+    // Fails if the winning product does not fit in an i64
+    pub fn max_run_with(&self, len: usize, search: &RunSearch) -> Result<Option<Run>, ProductOverflow> {
+        Ok(self.ranked(len, 1, search)?.pop())
+    }
+
+    // Same as max_run_with, for runs whose products overflow i64
+    pub fn max_run_exact(&self, len: usize, search: &RunSearch) -> Option<Run<BigInt>> {
+        self.ranked_exact(len, 1, search).pop()
+    }
+
+    // The k runs with the largest products, best first, ties in scan order
+    pub fn top_runs(&self, len: usize, k: usize) -> Result<Vec<Run>, ProductOverflow> {
+        self.top_runs_with(len, k, &RunSearch::default())
+    }
+
+    // Fails if any of the k products does not fit in an i64
+    pub fn top_runs_with(&self, len: usize, k: usize, search: &RunSearch) -> Result<Vec<Run>, ProductOverflow> {
+        self.ranked(len, k, search)
+    }
+
+    pub fn top_runs_exact(&self, len: usize, k: usize, search: &RunSearch) -> Vec<Run<BigInt>> {
+        self.ranked_exact(len, k, search)
+    }
+
+    // 0 if no run fits
+    pub fn max_product(&self, len: usize) -> Result<i64, ProductOverflow> {
+        Ok(self.max_run(len)?.map_or(0, |run| run.product))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_grid(rows: usize, cols: usize, range: i64) -> Grid {
+        let mut x = 77_u64;
+        let cells = (0..rows * cols)
+            .map(|_| {
+                x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                ((x >> 33) % (2 * range as u64 + 1)) as i64 - range
+            })
+            .collect();
+        Grid { cells, rows, cols }
+    }
+
+    #[test]
+    fn line_walk_matches_prod_run() {
+        let grid = sample_grid(9, 7, 6);
+        let searches = [RunSearch::all_directions(), RunSearch::knight(), RunSearch::new(&[(3, -2)])];
+        for search in searches {
+            for wrap in [false, true] {
+                let search = search.clone().wrapping(wrap);
+                for len in 1..=12 {
+                    let reference: Vec<Candidate> = search
+                        .dirs()
+                        .iter()
+                        .flat_map(|&dir| grid.scan_dir(dir, len, wrap))
+                        .collect();
+                    assert_eq!(grid.candidates::<i64>(len, &search), reference, "{search:?} len = {len}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn big_integer_path_matches_i64_path() {
+        let grid = sample_grid(6, 6, 9);
+        let search = RunSearch::all_directions().wrapping(true);
+        for len in 1..=8 {
+            let small: Vec<Candidate<BigInt>> = grid
+                .candidates::<i64>(len, &search)
+                .into_iter()
+                .map(|(p, start, dir)| (BigInt::from(p), start, dir))
+                .collect();
+            assert_eq!(grid.candidates::<BigInt>(len, &search), small, "len = {len}");
+        }
+    }
+
+    #[test]
+    fn long_runs_use_big_integers() {
+        let grid = sample_grid(3, 40, 99);
+        assert!(!lines::fits_i64(&grid.cells, 30));
+        let search = RunSearch::default();
+        let run = grid.max_run_exact(30, &search).unwrap();
+        let want: BigInt = run.values.iter().map(|&v| BigInt::from(v)).product();
+        assert_eq!(run.product, want);
+        assert!(run.product > BigInt::from(i64::MAX));
+        // Brute force over every horizontal window
+        let best = (0..3)
+            .flat_map(|r| (0..=10).map(move |c| (r, c)))
+            .map(|(r, c)| (0..30).map(|k| BigInt::from(grid.at(r, c + k))).product::<BigInt>())
+            .max()
+            .unwrap();
+        assert_eq!(run.product, best);
+    }
+}
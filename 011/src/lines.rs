@@ -0,0 +1,126 @@
+// Sliding-window products along grid lines.
+//
+// For a direction d the grid splits into lines: maximal chains of cells,
+// each one step d from the last. Every run in direction d is a window of
+// exactly one line, so walking each line once with a rolling product
+// visits all runs at O(1) per run instead of O(len).
+//
+// As in 008's `max_product_rolling`, zeros are counted rather than
+// multiplied in, so the outgoing value can always be divided back out
+// exactly. On a torus the lines close into cycles and windows wrap around.
+
+use num_bigint::BigInt;
+
+// A product that can be grown and shrunk one cell at a time
+pub(crate) trait RunProduct: Clone + Ord {
+    fn one() -> Self;
+    fn zero() -> Self;
+    fn mul(&mut self, v: i64);
+    // Invariant: v is non-zero and was multiplied in earlier, so this is exact
+    fn div(&mut self, v: i64);
+}
+
+impl RunProduct for i64 {
+    fn one() -> Self {
+        1
+    }
+
+    fn zero() -> Self {
+        0
+    }
+
+    fn mul(&mut self, v: i64) {
+        *self *= v;
+    }
+
+    fn div(&mut self, v: i64) {
+        *self /= v;
+    }
+}
+
+impl RunProduct for BigInt {
+    fn one() -> Self {
+        BigInt::from(1)
+    }
+
+    fn zero() -> Self {
+        BigInt::from(0)
+    }
+
+    fn mul(&mut self, v: i64) {
+        *self *= v;
+    }
+
+    fn div(&mut self, v: i64) {
+        *self /= v;
+    }
+}
+
+// Whether every product of len of these cells is sure to fit in an i64
+pub(crate) fn fits_i64(cells: &[i64], len: usize) -> bool {
+    let max_abs = cells.iter().map(|v| v.unsigned_abs()).max().unwrap_or(0);
+    max_abs <= 1
+        || u32::try_from(len)
+            .ok()
+            .and_then(|len| max_abs.checked_pow(len))
+            .is_some_and(|p| p <= i64::MAX as u64)
+}
+
+// Calls `visit` with (product, start) for every window of len cells along
+// one line. The product is lent rather than cloned, so a caller that keeps
+// few windows copies few products. A cyclic line has a window starting at
+// each of its cells; a window longer than the cycle goes round it more
+// than once.
+pub(crate) fn line_windows<P: RunProduct, C: Copy>(
+    values: &[i64],
+    cells: &[C],
+    cyclic: bool,
+    len: usize,
+    mut visit: impl FnMut(&P, C),
+) {
+    let n = values.len();
+    let starts = match cyclic {
+        true => n,
+        false if n >= len => n - len + 1,
+        false => return,
+    };
+    let at = |i: usize| values[i % n];
+
+    let (mut product, zero) = (P::one(), P::zero());
+    let mut zeros = 0_usize;
+    for i in 0..starts + len - 1 {
+        match at(i) {
+            0 => zeros += 1,
+            v => product.mul(v),
+        }
+        // Invariant: the window is now [i + 1 - len, i]
+        if i >= len {
+            match at(i - len) {
+                0 => zeros -= 1,
+                v => product.div(v),
+            }
+        }
+        if i + 1 >= len {
+            visit(if zeros > 0 { &zero } else { &product }, cells[i + 1 - len]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cyclic_windows_wrap() {
+        let values = [2, 0, 3];
+        let cells = [(0, 0), (0, 1), (0, 2)];
+        let mut out: Vec<(i64, (usize, usize))> = Vec::new();
+        line_windows(&values, &cells, true, 2, |&p, c| out.push((p, c)));
+        assert_eq!(out, vec![(0, (0, 0)), (0, (0, 1)), (6, (0, 2))]);
+
+        // Longer than the cycle, so cells repeat
+        out.clear();
+        line_windows(&[2, -3], &cells[..2], true, 5, |&p, c| out.push((p, c)));
+        assert_eq!(out, vec![(2 * -3 * 2 * -3 * 2, (0, 0)), (-3 * 2 * -3 * 2 * -3, (0, 1))]);
+    }
+}
@@ -1,139 +1,137 @@
-struct Grid {
-    cells: Vec<u64>,
-    rows: usize,
-    cols: usize,
-}
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::PathBuf;
+
+use euler011::{Grid, GridFormat, Run, RunSearch};
 
-#[derive(Debug)]
-enum GridParseError {
-    Empty,
-    BadNumber,
-    Ragged,
+const GRID: &str = include_str!("grid.txt");
+
+enum Source {
+    Builtin,
+    Stdin,
+    File(PathBuf),
 }
 
-impl Grid {
-    const DIRS: [(isize, isize); 4] = [(0,1), (1,0), (1,1), (1,-1)];
+struct Args {
+    source: Source,
+    format: Option<GridFormat>,
+    run_length: usize,
+    top: Option<usize>,
+    verbose: bool,
+    search: RunSearch,
+}
 
-    fn try_from_str(s: &str) -> Result<Self, GridParseError> {
-        let mut cells: Vec<u64> = Vec::new();
-        let mut rows = 0usize;
-        let mut cols: Option<usize> = None;
+const USAGE: &str = "usage: euler011 [-n LEN] [--top K] [-v] [--dirs 4|8|knight | --dir DR,DC ...] [--wrap]
+               [--format ws|csv|json] [--file PATH | --stdin]";
 
-        for line in s.lines() {
-            let mut this_cols = 0usize;
+fn parse_args() -> Result<Args, Box<dyn Error>> {
+    let mut args = Args { source: Source::Builtin, format: None, run_length: 4, top: None, verbose: false, search: RunSearch::default() };
+    let mut custom: Vec<(isize, isize)> = Vec::new();
+    let mut wrap = false;
+    let mut it = env::args().skip(1);
 
-            for n in line.split_whitespace() {
-                let val = n.parse::<u64>().map_err(|_| GridParseError::BadNumber)?;
-                cells.push(val);
-                this_cols += 1;
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "-n" | "--length" => {
+                let v = it.next().ok_or("missing value for --length")?;
+                args.run_length = v.parse().map_err(|e| format!("Invalid run length {v:?}: {e}"))?;
             }
-
-            if this_cols == 0 {
-                continue;
+            "-k" | "--top" => {
+                let v = it.next().ok_or("missing value for --top")?;
+                args.top = Some(v.parse().map_err(|e| format!("Invalid count {v:?}: {e}"))?);
             }
-
-            let expected = cols.get_or_insert(this_cols);
-            if *expected != this_cols {
-                return Err(GridParseError::Ragged);
+            "-v" | "--verbose" => args.verbose = true,
+            "--dirs" => {
+                args.search = match it.next().ok_or("missing value for --dirs")?.as_str() {
+                    "4" => RunSearch::default(),
+                    "8" => RunSearch::all_directions(),
+                    "knight" => RunSearch::knight(),
+                    v => return Err(format!("Unknown direction set {v:?} (expected 4, 8 or knight)").into()),
+                };
             }
-
-            rows += 1;
-        }
-
-        let cols = cols.ok_or(GridParseError::Empty)?;
-        if rows == 0 {
-            return Err(GridParseError::Empty);
-        }
-
-        if cells.len() != rows * cols {
-            return Err(GridParseError::Ragged);
+            "--dir" => custom.push(parse_dir(&it.next().ok_or("missing value for --dir")?)?),
+            "--wrap" => wrap = true,
+            "--format" => {
+                let v = it.next().ok_or("missing value for --format")?;
+                args.format = Some(v.parse()?);
+            }
+            "-f" | "--file" => {
+                args.source = Source::File(it.next().ok_or("missing value for --file")?.into());
+            }
+            "--stdin" | "-" => args.source = Source::Stdin,
+            "-h" | "--help" => return Err(USAGE.into()),
+            s => return Err(format!("Unknown argument {s:?}\n{USAGE}").into()),
         }
-
-        Ok(Self { cells, rows, cols })
     }
-
-    fn at(&self, r: usize, c: usize) -> u64 {
-        self.cells[r * self.cols + c]
-    }
-
-    fn prod_run(&self, r: usize, c: usize, dr: isize, dc: isize, len: usize) -> u64 {
-        (0..len).fold(1u64, |prod, k| {
-            let rr = (r as isize + dr * (k as isize)) as usize;
-            let cc = (c as isize + dc * (k as isize)) as usize;
-            prod * self.at(rr, cc)
-        })
+    if !custom.is_empty() {
+        args.search = RunSearch::new(&custom);
     }
+    args.search = args.search.wrapping(wrap);
+    Ok(args)
+}
 
-    fn scan_starts<I>(&self, starts: I, dr: isize, dc: isize, len: usize) -> u64
-        where
-            I: IntoIterator<Item = (usize, usize)>,
-    {
-        starts
-            .into_iter()
-            .map(|(r, c)| self.prod_run(r, c, dr, dc, len))
-            .max()
-            .unwrap_or(0)
+// "DR,DC", e.g. "1,-1" or "2,1"
+fn parse_dir(s: &str) -> Result<(isize, isize), Box<dyn Error>> {
+    let invalid = || format!("Invalid direction {s:?} (expected DR,DC other than 0,0)");
+    let (dr, dc) = s.split_once(',').ok_or_else(invalid)?;
+    let dir = (dr.trim().parse().map_err(|_| invalid())?, dc.trim().parse().map_err(|_| invalid())?);
+    if dir == (0, 0) {
+        return Err(invalid().into());
     }
+    Ok(dir)
+}
 
-    fn scan_dir(&self, dr: isize, dc: isize, len: usize) -> u64 {
-        let Grid { rows, cols, .. } = *self;
-
-        match (dr, dc) {
-            (0, 1) => self.scan_starts(
-                (0..rows).flat_map(|r| (0..=cols - len).map(move |c| (r, c))),
-                dr, dc, len
-            ),
-            (1, 0) => self.scan_starts(
-                (0..=rows - len).flat_map(|r| (0..cols).map(move |c| (r, c))),
-                dr, dc, len
-            ),
-            (1, 1) => self.scan_starts(
-                (0..=rows - len).flat_map(|r| (0..=cols - len).map(move |c| (r, c))),
-                dr, dc, len
-            ),
-            (1, -1) => self.scan_starts(
-                (0..=rows - len).flat_map(|r| ((len - 1)..cols).map(move |c| (r, c))),
-                dr, dc, len
-            ),
-            _ => 0,
+fn load_grid(source: Source, format: Option<GridFormat>) -> Result<Grid, Box<dyn Error>> {
+    let format_or_default = format.unwrap_or(GridFormat::Whitespace);
+    match source {
+        Source::Builtin => Ok(Grid::parse(GRID, format_or_default)?),
+        Source::Stdin => {
+            Grid::read(io::stdin().lock(), format_or_default).map_err(|e| format!("<stdin>: {e}").into())
         }
-    }
-
-    fn max_product(&self, len: usize) -> u64 {
-        if len == 0 || len > self.rows || len > self.cols {
-            return 0;
+        Source::File(path) => {
+            let format = format.unwrap_or_else(|| GridFormat::from_path(&path));
+            let file = File::open(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+            Grid::read(BufReader::new(file), format).map_err(|e| format!("{}: {e}", path.display()).into())
         }
-
-       Self::DIRS.iter()
-        .map(|&(dr, dc)| self.scan_dir(dr, dc, len))
-        .max()
-        .unwrap_or(0)
     }
 }
 
-fn main() {
-    const GRID: &str = "\
-08 02 22 97 38 15 00 40 00 75 04 05 07 78 52 12 50 77 91 08
-49 49 99 40 17 81 18 57 60 87 17 40 98 43 69 48 04 56 62 00
-81 49 31 73 55 79 14 29 93 71 40 67 53 88 30 03 49 13 36 65
-52 70 95 23 04 60 11 42 69 24 68 56 01 32 56 71 37 02 36 91
-22 31 16 71 51 67 63 89 41 92 36 54 22 40 40 28 66 33 13 80
-24 47 32 60 99 03 45 02 44 75 33 53 78 36 84 20 35 17 12 50
-32 98 81 28 64 23 67 10 26 38 40 67 59 54 70 66 18 38 64 70
-67 26 20 68 02 62 12 20 95 63 94 39 63 08 40 91 66 49 94 21
-24 55 58 05 66 73 99 26 97 17 78 78 96 83 14 88 34 89 63 72
-21 36 23 09 75 00 76 44 20 45 35 14 00 61 33 97 34 31 33 95
-78 17 53 28 22 75 31 67 15 94 03 80 04 62 16 14 09 53 56 92
-16 39 05 42 96 35 31 47 55 58 88 24 00 17 54 24 36 29 85 57
-86 56 00 48 35 71 89 07 05 44 44 37 44 60 21 58 51 54 17 58
-19 80 81 68 05 94 47 69 28 73 92 13 86 52 17 77 04 89 55 40
-04 52 08 83 97 35 99 16 07 97 57 32 16 26 26 79 33 27 98 66
-88 36 68 87 57 62 20 72 03 46 33 67 46 55 12 32 63 93 53 69
-04 42 16 73 38 25 39 11 24 94 72 18 08 46 29 32 40 62 76 36
-20 69 36 41 72 30 23 88 34 62 99 69 82 67 59 85 74 04 36 16
-20 73 35 29 78 31 90 01 74 31 49 71 48 86 81 16 23 57 05 54
-01 70 54 71 83 51 54 69 16 92 33 48 61 43 52 01 89 19 67 48";
+fn print_run<P: Display>(grid: &Grid, run: &Run<P>, verbose: bool) {
+    println!("{}", run.product);
+    if verbose {
+        let values: Vec<String> = run.values.iter().map(|v| v.to_string()).collect();
+        println!("start: row {}, column {}", run.start.0, run.start.1);
+        println!("direction: {:?}", run.dir);
+        println!("values: {}", values.join(" x "));
+        print!("{}", grid.render(run));
+    }
+}
 
-    let grid = Grid::try_from_str(GRID).unwrap();
-    println!("{}", grid.max_product(4));
+fn main() -> Result<(), Box<dyn Error>> {
+    let Args { source, format, run_length, top, verbose, search } = parse_args()?;
+    let grid = load_grid(source, format)?;
+
+    // The exact search only falls back to big integers when i64 could overflow
+    let runs = match top {
+        Some(k) => grid.top_runs_exact(run_length, k, &search),
+        None => grid.max_run_exact(run_length, &search).into_iter().collect(),
+    };
+    if runs.is_empty() && top != Some(0) {
+        return Err(format!(
+            "No run of length {run_length} fits in the {}x{} grid",
+            grid.rows(),
+            grid.cols()
+        )
+        .into());
+    }
+    for (i, run) in runs.iter().enumerate() {
+        if verbose && i > 0 {
+            println!();
+        }
+        print_run(&grid, run, verbose);
+    }
+    Ok(())
 }
@@ -0,0 +1,485 @@
+// Grids with any number of dimensions.
+//
+// Cells are stored row-major: the last axis varies fastest, and
+// strides[i] is how far apart in `cells` two neighbours along axis i are.
+// A direction is one step per axis. Up to reversal, a grid of N dimensions
+// has (3^N - 1) / 2 directions to its neighbours, so 4 in 2D and 13 in 3D.
+//
+// The run search walks lines: each direction splits the grid into lines
+// and every run is a window of exactly one of them. On a torus the lines
+// close into cycles. `Grid` has no search of its own and ranks its runs
+// through this one. Ranking keeps only the k best runs in a heap, so memory
+// does not grow with the grid.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fmt;
+
+use num_bigint::BigInt;
+
+use crate::lines::{self, RunProduct};
+use crate::Grid;
+
+// (product, flat index of the first cell, index into the directions)
+pub(crate) type Candidate<P = i64> = (P, usize, usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NdGrid {
+    cells: Vec<i64>,
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShapeError {
+    // A grid needs at least one axis
+    NoAxes,
+    // `found` cells, which is not the number of positions in `shape`
+    CellCount { shape: Vec<usize>, found: usize },
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::NoAxes => write!(f, "grid shape has no axes"),
+            ShapeError::CellCount { shape, found } => write!(f, "{found} cells do not fill shape {shape:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+// A run ranked by the i64 search whose product is outside the i64 range.
+// The `_exact` searches return it as a big integer instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProductOverflow {
+    pub product: BigInt,
+}
+
+impl fmt::Display for ProductOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "product {} does not fit in i64; use the exact search", self.product)
+    }
+}
+
+impl std::error::Error for ProductOverflow {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NdRun<P = i64> {
+    pub product: P,
+    // Coordinates of the first cell, one per axis
+    pub start: Vec<usize>,
+    // Step along each axis between consecutive cells
+    pub dir: Vec<isize>,
+    // Cell values from the start onwards
+    pub values: Vec<i64>,
+}
+
+// Coordinates of the kth cell of a run
+fn cell(start: &[usize], dir: &[isize], k: usize) -> Vec<usize> {
+    start.iter().zip(dir).map(|(&x, &d)| (x as isize + d * k as isize) as usize).collect()
+}
+
+impl<P> NdRun<P> {
+    // Coordinates of every cell in the run
+    pub fn cells(&self) -> impl Iterator<Item = Vec<usize>> + '_ {
+        (0..self.values.len()).map(|k| cell(&self.start, &self.dir, k))
+    }
+}
+
+// Orders runs so that the better one is greater: the larger product, then
+// the earlier direction, then the earlier start
+fn rank<P: Ord>((p, start, d): (&P, usize, usize), (q, q_start, q_d): (&P, usize, usize)) -> Ordering {
+    p.cmp(q).then_with(|| (q_d, q_start).cmp(&(d, start)))
+}
+
+struct Ranked<P>(Candidate<P>);
+
+impl<P: Ord> Ord for Ranked<P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (&self.0, &other.0);
+        rank((&a.0, a.1, a.2), (&b.0, b.1, b.2))
+    }
+}
+
+impl<P: Ord> PartialOrd for Ranked<P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: Ord> PartialEq for Ranked<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<P: Ord> Eq for Ranked<P> {}
+
+// The k best runs seen so far, in a min-heap whose top is the worst one
+// kept. A new run only has to beat that one, and its product is cloned
+// only if it gets in. With k = 1 this is a running best.
+struct TopRuns<P> {
+    k: usize,
+    heap: BinaryHeap<Reverse<Ranked<P>>>,
+}
+
+impl<P: Ord + Clone> TopRuns<P> {
+    fn new(k: usize) -> Self {
+        Self { k, heap: BinaryHeap::with_capacity(k.min(1 << 16)) }
+    }
+
+    fn offer(&mut self, product: &P, start: usize, d: usize) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(Ranked((product.clone(), start, d))));
+            return;
+        }
+        let Some(mut worst) = self.heap.peek_mut() else {
+            return;
+        };
+        let Reverse(Ranked((p, s, e))) = &*worst;
+        if rank((product, start, d), (p, *s, *e)) == Ordering::Greater {
+            *worst = Reverse(Ranked((product.clone(), start, d)));
+        }
+    }
+
+    // Best first
+    fn into_sorted(self) -> Vec<Candidate<P>> {
+        self.heap.into_sorted_vec().into_iter().map(|Reverse(Ranked(cand))| cand).collect()
+    }
+}
+
+impl NdGrid {
+    // `cells` in row-major order, which must fill `shape` exactly
+    pub fn new(shape: &[usize], cells: Vec<i64>) -> Result<Self, ShapeError> {
+        if shape.is_empty() {
+            return Err(ShapeError::NoAxes);
+        }
+        let size = shape.iter().try_fold(1_usize, |size, &n| size.checked_mul(n));
+        if size != Some(cells.len()) {
+            return Err(ShapeError::CellCount { shape: shape.to_vec(), found: cells.len() });
+        }
+        Ok(Self::with_shape(shape, cells))
+    }
+
+    // Invariant: shape is non-empty and cells fill it
+    fn with_shape(shape: &[usize], cells: Vec<i64>) -> Self {
+        let mut strides = vec![1; shape.len()];
+        for i in (0..shape.len() - 1).rev() {
+            strides[i] = strides[i + 1] * shape[i + 1];
+        }
+        Self { cells, shape: shape.to_vec(), strides }
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    pub fn strides(&self) -> &[usize] {
+        &self.strides
+    }
+
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    pub fn at(&self, coords: &[usize]) -> i64 {
+        self.cells[self.index(coords)]
+    }
+
+    fn index(&self, coords: &[usize]) -> usize {
+        assert_eq!(coords.len(), self.ndim(), "expected {} coordinates", self.ndim());
+        coords
+            .iter()
+            .zip(&self.shape)
+            .zip(&self.strides)
+            .map(|((&x, &n), &s)| {
+                assert!(x < n, "coordinate {x} is outside an axis of length {n}");
+                x * s
+            })
+            .sum()
+    }
+
+    fn coords(&self, index: usize) -> Vec<usize> {
+        self.shape.iter().zip(&self.strides).map(|(&n, &s)| index / s % n).collect()
+    }
+
+    // One direction from each pair {d, -d} of neighbour steps: those whose
+    // first non-zero step is positive. Axis steps come first, then
+    // diagonals by how many axes they move along; within each group the
+    // order is lexicographic with 0 < 1 < -1. In 2D that is Grid::DIRS.
+    pub fn directions(ndim: usize) -> Vec<Vec<isize>> {
+        let mut dirs: Vec<Vec<isize>> = vec![Vec::new()];
+        for _ in 0..ndim {
+            dirs = dirs
+                .into_iter()
+                .flat_map(|d| {
+                    [0, 1, -1].into_iter().map(move |step| {
+                        let mut d = d.clone();
+                        d.push(step);
+                        d
+                    })
+                })
+                .collect();
+        }
+        dirs.retain(|d| d.iter().find(|&&step| step != 0).is_some_and(|&step| step > 0));
+        // Stable, so the lexicographic order holds within each group
+        dirs.sort_by_key(|d| d.iter().filter(|&&step| step != 0).count());
+        dirs
+    }
+
+    // Calls `visit` with the flat indices of every line in direction `dir`,
+    // each in walking order. One buffer is reused for every line. With
+    // wrap, every line is a cycle and starts at its first cell in flat
+    // order.
+    fn for_each_line(&self, dir: &[isize], wrap: bool, mut visit: impl FnMut(&[usize])) {
+        let mut line = Vec::new();
+        if wrap {
+            let mut seen = vec![false; self.cells.len()];
+            let mut coords = vec![0; self.ndim()];
+            for start in 0..self.cells.len() {
+                if seen[start] {
+                    continue;
+                }
+                line.clear();
+                for ((x, &n), &s) in coords.iter_mut().zip(&self.shape).zip(&self.strides) {
+                    *x = start / s % n;
+                }
+                let mut index = start;
+                while !seen[index] {
+                    seen[index] = true;
+                    line.push(index);
+                    index = self.wrapping_step(&mut coords, index, dir);
+                }
+                visit(&line);
+            }
+            return;
+        }
+
+        // Steps that stay inside the grid move a fixed distance in `cells`
+        let offset: isize = dir.iter().zip(&self.strides).map(|(&d, &s)| d * s as isize).sum();
+        for start in 0..self.cells.len() {
+            // Invariant: a line starts where the step before it leaves the
+            // grid, and it is as long as its tightest axis allows
+            let (mut first, mut cells) = (false, usize::MAX);
+            for ((&d, &n), &s) in dir.iter().zip(&self.shape).zip(&self.strides) {
+                let (x, step) = (start / s % n, d.unsigned_abs());
+                if d > 0 {
+                    first |= x < step;
+                    cells = cells.min((n - 1 - x) / step + 1);
+                } else if d < 0 {
+                    first |= x + step >= n;
+                    cells = cells.min(x / step + 1);
+                }
+            }
+            if !first {
+                continue;
+            }
+            line.clear();
+            line.extend((0..cells).map(|k| (start as isize + offset * k as isize) as usize));
+            visit(&line);
+        }
+    }
+
+    // Moves `coords` one step along `dir` on the torus and returns the new
+    // flat index
+    fn wrapping_step(&self, coords: &mut [usize], mut index: usize, dir: &[isize]) -> usize {
+        for (((x, &d), &n), &s) in coords.iter_mut().zip(dir).zip(&self.shape).zip(&self.strides) {
+            let y = (*x as isize + d).rem_euclid(n as isize) as usize;
+            index = index - *x * s + y * s;
+            *x = y;
+        }
+        index
+    }
+
+    // Calls `visit` with (product, flat start, direction index) for every
+    // run of `len` cells
+    fn for_each_run<P: RunProduct>(&self, len: usize, dirs: &[Vec<isize>], wrap: bool, mut visit: impl FnMut(&P, usize, usize)) {
+        if len == 0 {
+            return;
+        }
+        let mut values = Vec::new();
+        for (d, dir) in dirs.iter().enumerate() {
+            assert_eq!(dir.len(), self.ndim(), "direction {dir:?} does not match {} axes", self.ndim());
+            assert!(dir.iter().any(|&step| step != 0), "direction {dir:?} never moves");
+            self.for_each_line(dir, wrap, |line| {
+                values.clear();
+                values.extend(line.iter().map(|&i| self.cells[i]));
+                lines::line_windows(&values, line, wrap, len, |product: &P, start| visit(product, start, d));
+            });
+        }
+    }
+
+    // Every run of `len` cells: direction by direction, then in cell order.
+    // Only the tests need them all.
+    #[cfg(test)]
+    pub(crate) fn candidates<P: RunProduct>(&self, len: usize, dirs: &[Vec<isize>], wrap: bool) -> Vec<Candidate<P>> {
+        let mut all = Vec::new();
+        self.for_each_run::<P>(len, dirs, wrap, |product, start, d| all.push((product.clone(), start, d)));
+        // Lines visit starts out of order; restore the scan order
+        all.sort_by_key(|&(_, start, d)| (d, start));
+        all
+    }
+
+    // The k best runs, best first. Ties go to the earlier direction, then
+    // the earlier start, which is the scan order.
+    fn best<P: RunProduct>(&self, len: usize, k: usize, dirs: &[Vec<isize>], wrap: bool) -> Vec<Candidate<P>> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut top = TopRuns::new(k);
+        self.for_each_run::<P>(len, dirs, wrap, |product, start, d| top.offer(product, start, d));
+        top.into_sorted()
+    }
+
+    // The k best runs with exact products: i64 when none can overflow, and
+    // big integers otherwise. `Grid` ranks its runs here too.
+    pub(crate) fn ranked_exact(&self, len: usize, k: usize, dirs: &[Vec<isize>], wrap: bool) -> Vec<Candidate<BigInt>> {
+        if lines::fits_i64(&self.cells, len) {
+            let best = self.best::<i64>(len, k, dirs, wrap);
+            best.into_iter().map(|(p, start, d)| (BigInt::from(p), start, d)).collect()
+        } else {
+            self.best::<BigInt>(len, k, dirs, wrap)
+        }
+    }
+
+    // The same k runs with i64 products, or the first product that does
+    // not fit
+    pub(crate) fn ranked(
+        &self,
+        len: usize,
+        k: usize,
+        dirs: &[Vec<isize>],
+        wrap: bool,
+    ) -> Result<Vec<Candidate>, ProductOverflow> {
+        if lines::fits_i64(&self.cells, len) {
+            return Ok(self.best::<i64>(len, k, dirs, wrap));
+        }
+        self.best::<BigInt>(len, k, dirs, wrap)
+            .into_iter()
+            .map(|(product, start, d)| match i64::try_from(&product) {
+                Ok(p) => Ok((p, start, d)),
+                Err(_) => Err(ProductOverflow { product }),
+            })
+            .collect()
+    }
+
+    fn to_run<P>(&self, (product, start, d): Candidate<P>, len: usize, dirs: &[Vec<isize>]) -> NdRun<P> {
+        let (start, dir) = (self.coords(start), dirs[d].clone());
+        let values = (0..len).map(|k| self.at(&cell(&start, &dir, k))).collect();
+        NdRun { product, start, dir, values }
+    }
+
+    // The run with the largest product over all neighbour directions
+    pub fn max_run(&self, len: usize) -> Result<Option<NdRun>, ProductOverflow> {
+        self.max_run_with(len, &Self::directions(self.ndim()))
+    }
+
+    // Fails if the winning product does not fit in an i64
+    pub fn max_run_with(&self, len: usize, dirs: &[Vec<isize>]) -> Result<Option<NdRun>, ProductOverflow> {
+        Ok(self.top_runs_with(len, 1, dirs)?.pop())
+    }
+
+    pub fn max_run_exact(&self, len: usize, dirs: &[Vec<isize>]) -> Option<NdRun<BigInt>> {
+        self.top_runs_exact(len, 1, dirs).pop()
+    }
+
+    pub fn top_runs(&self, len: usize, k: usize) -> Result<Vec<NdRun>, ProductOverflow> {
+        self.top_runs_with(len, k, &Self::directions(self.ndim()))
+    }
+
+    pub fn top_runs_with(&self, len: usize, k: usize, dirs: &[Vec<isize>]) -> Result<Vec<NdRun>, ProductOverflow> {
+        let best = self.ranked(len, k, dirs, false)?;
+        Ok(best.into_iter().map(|cand| self.to_run(cand, len, dirs)).collect())
+    }
+
+    pub fn top_runs_exact(&self, len: usize, k: usize, dirs: &[Vec<isize>]) -> Vec<NdRun<BigInt>> {
+        self.ranked_exact(len, k, dirs, false).into_iter().map(|cand| self.to_run(cand, len, dirs)).collect()
+    }
+
+    // 0 if no run fits
+    pub fn max_product(&self, len: usize) -> Result<i64, ProductOverflow> {
+        Ok(self.max_run(len)?.map_or(0, |run| run.product))
+    }
+}
+
+impl From<&Grid> for NdGrid {
+    fn from(grid: &Grid) -> Self {
+        Self::with_shape(&[grid.rows, grid.cols], grid.cells.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strides_are_row_major() {
+        let grid = NdGrid::new(&[2, 3, 4], (0..24).collect()).unwrap();
+        assert_eq!(grid.strides(), &[12, 4, 1]);
+        assert_eq!(grid.at(&[1, 2, 3]), 23);
+        assert_eq!(grid.coords(17), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn directions_are_half_the_neighbours() {
+        for n in 1..=4 {
+            let dirs = NdGrid::directions(n);
+            assert_eq!(dirs.len(), (3_usize.pow(n as u32) - 1) / 2);
+            for d in &dirs {
+                let back: Vec<isize> = d.iter().map(|s| -s).collect();
+                assert!(!dirs.contains(&back), "{d:?} and its reverse");
+            }
+        }
+        let grid_dirs: Vec<Vec<isize>> = Grid::DIRS.iter().map(|&(dr, dc)| vec![dr, dc]).collect();
+        assert_eq!(NdGrid::directions(2), grid_dirs);
+        assert_eq!(NdGrid::directions(3)[..4], [vec![0, 0, 1], vec![0, 1, 0], vec![1, 0, 0], vec![0, 1, 1]]);
+    }
+
+    #[test]
+    fn lines_cover_every_cell_once() {
+        let grid = NdGrid::new(&[3, 2, 4], vec![0; 24]).unwrap();
+        let flat = NdGrid::new(&[4, 6], vec![0; 24]).unwrap();
+        let steps = [[0, 1], [1, 0], [1, 1], [1, -1], [-2, 1], [3, 2]].map(|d| d.to_vec());
+        let cases = NdGrid::directions(3).into_iter().map(|d| (&grid, d)).chain(steps.into_iter().map(|d| (&flat, d)));
+        for (grid, dir) in cases {
+            for wrap in [false, true] {
+                let mut cells = Vec::new();
+                grid.for_each_line(&dir, wrap, |line| cells.extend_from_slice(line));
+                cells.sort_unstable();
+                assert_eq!(cells, (0..24).collect::<Vec<_>>(), "dir = {dir:?}, wrap = {wrap}");
+            }
+        }
+    }
+    // The sort the heap replaces: stable, so ties keep scan order
+    fn sorted<P: RunProduct>(grid: &NdGrid, len: usize, k: usize, dirs: &[Vec<isize>], wrap: bool) -> Vec<Candidate<P>> {
+        let mut all = grid.candidates::<P>(len, dirs, wrap);
+        all.sort_by(|a, b| b.0.cmp(&a.0));
+        all.truncate(k);
+        all
+    }
+
+    #[test]
+    fn heap_ranking_matches_sorting() {
+        // Few distinct values, so most products tie
+        let mut x = 5_u64;
+        let cells: Vec<i64> = (0..60)
+            .map(|_| {
+                x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (x >> 33) as i64 % 5 - 2
+            })
+            .collect();
+        let grid = NdGrid::new(&[3, 4, 5], cells).unwrap();
+        let dirs = NdGrid::directions(3);
+        for wrap in [false, true] {
+            for len in 1..=4 {
+                for k in [0, 1, 2, 7, 50, 1000] {
+                    let want = sorted::<i64>(&grid, len, k, &dirs, wrap);
+                    assert_eq!(grid.best::<i64>(len, k, &dirs, wrap), want, "len = {len}, k = {k}, wrap = {wrap}");
+                    let big = want.into_iter().map(|(p, start, d)| (BigInt::from(p), start, d)).collect::<Vec<_>>();
+                    assert_eq!(grid.best::<BigInt>(len, k, &dirs, wrap), big);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,352 @@
+// Grid parsing for whitespace, CSV and JSON input.
+//
+// Every format produces rows of signed integers. Positions in errors are
+// 1-based and count characters, so they match what an editor shows.
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridFormat {
+    // Numbers separated by spaces or tabs, one row per line
+    Whitespace,
+    // Comma-separated numbers, one row per line; fields may be quoted
+    Csv,
+    // An array of arrays of integers
+    Json,
+}
+
+impl GridFormat {
+    // Guesses the format from a file extension, defaulting to whitespace
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("csv") => GridFormat::Csv,
+            Some(e) if e.eq_ignore_ascii_case("json") => GridFormat::Json,
+            _ => GridFormat::Whitespace,
+        }
+    }
+}
+
+impl FromStr for GridFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "ws" | "whitespace" | "txt" => Ok(GridFormat::Whitespace),
+            "csv" => Ok(GridFormat::Csv),
+            "json" => Ok(GridFormat::Json),
+            _ => Err(format!("unknown grid format {s:?} (expected ws, csv or json)")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum GridParseError {
+    Io(io::Error),
+    Empty,
+    BadNumber { line: usize, column: usize, token: String },
+    // The row starting at `line` has `found` numbers; the first row had `expected`
+    Ragged { line: usize, expected: usize, found: usize },
+    // Malformed JSON structure
+    Syntax { line: usize, column: usize, expected: &'static str, found: String },
+}
+
+impl fmt::Display for GridParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridParseError::Io(e) => write!(f, "failed to read grid: {e}"),
+            GridParseError::Empty => write!(f, "grid has no numbers"),
+            GridParseError::BadNumber { line, column, token } => {
+                write!(f, "invalid number {token:?} at line {line}, column {column}")
+            }
+            GridParseError::Ragged { line, expected, found } => {
+                write!(f, "row at line {line} has {found} numbers, expected {expected}")
+            }
+            GridParseError::Syntax { line, column, expected, found } => {
+                write!(f, "expected {expected} at line {line}, column {column}, found {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GridParseError {}
+
+impl From<io::Error> for GridParseError {
+    fn from(e: io::Error) -> Self {
+        GridParseError::Io(e)
+    }
+}
+
+fn number(token: &str, line: usize, column: usize) -> Result<i64, GridParseError> {
+    token.parse().map_err(|_| GridParseError::BadNumber { line, column, token: token.to_string() })
+}
+
+// Collects rows and checks they all have the same length
+#[derive(Default)]
+struct Rows {
+    cells: Vec<i64>,
+    rows: usize,
+    cols: Option<usize>,
+}
+
+impl Rows {
+    fn push(&mut self, line: usize, row: &[i64]) -> Result<(), GridParseError> {
+        let expected = *self.cols.get_or_insert(row.len());
+        if expected != row.len() {
+            return Err(GridParseError::Ragged { line, expected, found: row.len() });
+        }
+        self.cells.extend_from_slice(row);
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(Vec<i64>, usize, usize), GridParseError> {
+        match self.cols {
+            Some(cols) if cols > 0 => Ok((self.cells, self.rows, cols)),
+            _ => Err(GridParseError::Empty),
+        }
+    }
+}
+
+// (1-based column, token) for each run of non-separator characters
+fn tokens(line: &str, is_sep: impl Fn(char) -> bool) -> Vec<(usize, &str)> {
+    let mut found = Vec::new();
+    let mut start: Option<(usize, usize)> = None;
+    for (column, (i, ch)) in line.char_indices().enumerate() {
+        match (is_sep(ch), start) {
+            (false, None) => start = Some((column + 1, i)),
+            (true, Some((col, from))) => {
+                found.push((col, &line[from..i]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((col, from)) = start {
+        found.push((col, &line[from..]));
+    }
+    found
+}
+
+fn parse_whitespace(s: &str) -> Result<Rows, GridParseError> {
+    let mut rows = Rows::default();
+    for (i, line) in s.lines().enumerate() {
+        let row = tokens(line, char::is_whitespace)
+            .into_iter()
+            .map(|(column, token)| number(token, i + 1, column))
+            .collect::<Result<Vec<_>, _>>()?;
+        // Blank lines are skipped
+        if !row.is_empty() {
+            rows.push(i + 1, &row)?;
+        }
+    }
+    Ok(rows)
+}
+
+fn parse_csv(s: &str) -> Result<Rows, GridParseError> {
+    let mut rows = Rows::default();
+    for (i, line) in s.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut row = Vec::new();
+        let mut column = 1;
+        for field in line.split(',') {
+            // Report the position of the field's first non-blank character
+            let lead = field.chars().take_while(|c| c.is_whitespace()).count();
+            let trimmed = field.trim();
+            let unquoted = trimmed
+                .strip_prefix('"')
+                .and_then(|t| t.strip_suffix('"'))
+                .unwrap_or(trimmed);
+            row.push(number(unquoted, i + 1, column + lead)?);
+            column += field.chars().count() + 1;
+        }
+        rows.push(i + 1, &row)?;
+    }
+    Ok(rows)
+}
+
+// A small JSON reader that only understands arrays of arrays of integers
+struct JsonCursor<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> JsonCursor<'a> {
+    fn new(s: &'a str) -> Self {
+        Self { chars: s.chars().peekable(), line: 1, column: 1 }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.chars.next()?;
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
+    }
+
+    // Skips whitespace and returns the next character without consuming it
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.bump();
+        }
+        self.chars.peek().copied()
+    }
+
+    fn error(&mut self, expected: &'static str) -> GridParseError {
+        let found = match self.chars.peek() {
+            Some(ch) => format!("{ch:?}"),
+            None => "end of input".to_string(),
+        };
+        GridParseError::Syntax { line: self.line, column: self.column, expected, found }
+    }
+
+    fn expect(&mut self, want: char, expected: &'static str) -> Result<(), GridParseError> {
+        if self.peek() != Some(want) {
+            return Err(self.error(expected));
+        }
+        self.bump();
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<i64, GridParseError> {
+        self.peek();
+        let (line, column) = (self.line, self.column);
+        let mut token = String::new();
+        while let Some(&ch) = self.chars.peek() {
+            if !(ch.is_ascii_alphanumeric() || matches!(ch, '-' | '+' | '.')) {
+                break;
+            }
+            token.push(ch);
+            self.bump();
+        }
+        if token.is_empty() {
+            return Err(self.error("a number"));
+        }
+        number(&token, line, column)
+    }
+
+    // Items of a `[...]` list, each read by `item`
+    fn list<T>(
+        &mut self,
+        expected: &'static str,
+        mut item: impl FnMut(&mut Self) -> Result<T, GridParseError>,
+    ) -> Result<Vec<T>, GridParseError> {
+        self.expect('[', "'['")?;
+        let mut items = Vec::new();
+        if self.peek() == Some(']') {
+            self.bump();
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(']') => {
+                    self.bump();
+                    return Ok(items);
+                }
+                _ => return Err(self.error(expected)),
+            }
+        }
+    }
+}
+
+fn parse_json(s: &str) -> Result<Rows, GridParseError> {
+    let mut cursor = JsonCursor::new(s);
+    let mut rows = Rows::default();
+    cursor.list("',' or ']' after a row", |c| {
+        c.peek();
+        let line = c.line;
+        let row = c.list("',' or ']' after a number", JsonCursor::number)?;
+        rows.push(line, &row)
+    })?;
+    if cursor.peek().is_some() {
+        return Err(cursor.error("end of input"));
+    }
+    Ok(rows)
+}
+
+pub(crate) fn parse(s: &str, format: GridFormat) -> Result<(Vec<i64>, usize, usize), GridParseError> {
+    match format {
+        GridFormat::Whitespace => parse_whitespace(s),
+        GridFormat::Csv => parse_csv(s),
+        GridFormat::Json => parse_json(s),
+    }?
+    .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(s: &str, format: GridFormat) -> (Vec<i64>, usize, usize) {
+        parse(s, format).unwrap()
+    }
+
+    #[test]
+    fn formats_agree() {
+        let want = (vec![8, -2, 22, 49, 49, -99], 2, 3);
+        assert_eq!(grid("08 -2 22\n\n49\t49 -99\n", GridFormat::Whitespace), want);
+        assert_eq!(grid("08,-2, 22\n49 , \"49\",-99\n", GridFormat::Csv), want);
+        assert_eq!(grid(" [[8, -2, 22],\n  [49, 49, -99]] ", GridFormat::Json), want);
+    }
+
+    #[test]
+    fn bad_numbers_report_position() {
+        let err = parse("1 2 3\n4 x5 6", GridFormat::Whitespace).unwrap_err();
+        assert!(matches!(err, GridParseError::BadNumber { line: 2, column: 3, ref token } if token == "x5"));
+
+        let err = parse("1,2\n3,  4.5", GridFormat::Csv).unwrap_err();
+        assert!(matches!(err, GridParseError::BadNumber { line: 2, column: 5, ref token } if token == "4.5"));
+
+        let err = parse("[[1, 2],\n [3, 1e3]]", GridFormat::Json).unwrap_err();
+        assert!(matches!(err, GridParseError::BadNumber { line: 2, column: 6, ref token } if token == "1e3"));
+
+        let err = parse("1 99999999999999999999", GridFormat::Whitespace).unwrap_err();
+        assert!(matches!(err, GridParseError::BadNumber { line: 1, column: 3, .. }));
+    }
+
+    #[test]
+    fn ragged_rows_report_line() {
+        let err = parse("1 2 3\n\n4 5", GridFormat::Whitespace).unwrap_err();
+        assert!(matches!(err, GridParseError::Ragged { line: 3, expected: 3, found: 2 }));
+
+        let err = parse("[[1],\n [2, 3]]", GridFormat::Json).unwrap_err();
+        assert!(matches!(err, GridParseError::Ragged { line: 2, expected: 1, found: 2 }));
+    }
+
+    #[test]
+    fn json_syntax_errors() {
+        let err = parse("[[1, 2] [3, 4]]", GridFormat::Json).unwrap_err();
+        assert!(matches!(err, GridParseError::Syntax { line: 1, column: 9, .. }));
+        assert_eq!(err.to_string(), "expected ',' or ']' after a row at line 1, column 9, found '['");
+
+        let err = parse("[[1, 2]", GridFormat::Json).unwrap_err();
+        assert!(matches!(err, GridParseError::Syntax { ref found, .. } if found == "end of input"));
+
+        let err = parse("[[1, 2]] x", GridFormat::Json).unwrap_err();
+        assert!(matches!(err, GridParseError::Syntax { column: 10, .. }));
+
+        let err = parse("[[1, ]]", GridFormat::Json).unwrap_err();
+        assert!(matches!(err, GridParseError::Syntax { column: 6, expected: "a number", .. }));
+    }
+
+    #[test]
+    fn empty_input() {
+        for format in [GridFormat::Whitespace, GridFormat::Csv] {
+            assert!(matches!(parse("\n  \n", format), Err(GridParseError::Empty)));
+        }
+        assert!(matches!(parse("[]", GridFormat::Json), Err(GridParseError::Empty)));
+        assert!(matches!(parse("[[], []]", GridFormat::Json), Err(GridParseError::Empty)));
+    }
+}
@@ -0,0 +1,143 @@
+// Sum queries on a grid: the rectangle with the largest sum, and paths
+// from the top-left cell to the bottom-right one.
+//
+// Path sums include both end cells, as in Problems 81 and 83. Sums are
+// i128: cells are i64, so no grid that fits in memory can overflow them.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::Grid;
+
+// An inclusive block of cells and their sum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub sum: i128,
+    pub top_left: (usize, usize),
+    pub bottom_right: (usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    pub sum: i128,
+    // (row, column) of every cell, from (0, 0) to the bottom-right corner
+    pub cells: Vec<(usize, usize)>,
+}
+
+impl Grid {
+    // The non-empty rectangle with the largest sum, in O(rows^2 * cols).
+    //
+    // For each pair of top and bottom rows, the column sums between them
+    // form a 1D array, and Kadane's scan finds its best subarray. Ties go
+    // to the first rectangle found, by top row, then bottom row, then
+    // column.
+    pub fn max_sum_rect(&self) -> Rect {
+        let mut best = Rect { sum: self.at(0, 0).into(), top_left: (0, 0), bottom_right: (0, 0) };
+        for top in 0..self.rows {
+            let mut column_sums = vec![0_i128; self.cols];
+            for bottom in top..self.rows {
+                for (c, sum) in column_sums.iter_mut().enumerate() {
+                    *sum += i128::from(self.at(bottom, c));
+                }
+                // Invariant: run is the best sum of a block ending at column c,
+                // and it starts at column `from`
+                let (mut run, mut from) = (0_i128, 0);
+                for (c, &sum) in column_sums.iter().enumerate() {
+                    if c == 0 || run < 0 {
+                        (run, from) = (sum, c);
+                    } else {
+                        run += sum;
+                    }
+                    if run > best.sum {
+                        best = Rect { sum: run, top_left: (top, from), bottom_right: (bottom, c) };
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    // The path with the largest sum moving only right or down, by dynamic
+    // programming over the cells in row-major order. On a tie the path
+    // comes from above.
+    pub fn max_path_sum(&self) -> Path {
+        let (rows, cols) = (self.rows, self.cols);
+        let mut best = vec![0_i128; rows * cols];
+        for r in 0..rows {
+            for c in 0..cols {
+                let before = match (r, c) {
+                    (0, 0) => 0,
+                    (0, _) => best[c - 1],
+                    (_, 0) => best[(r - 1) * cols],
+                    _ => best[(r - 1) * cols + c].max(best[r * cols + c - 1]),
+                };
+                best[r * cols + c] = before + i128::from(self.at(r, c));
+            }
+        }
+
+        // Walk back from the corner, always to the neighbour that was used
+        let mut cells = vec![(rows - 1, cols - 1)];
+        let (mut r, mut c) = (rows - 1, cols - 1);
+        while (r, c) != (0, 0) {
+            let up = (r > 0).then(|| best[(r - 1) * cols + c]);
+            let left = (c > 0).then(|| best[r * cols + c - 1]);
+            match (up, left) {
+                (Some(u), Some(l)) if u >= l => r -= 1,
+                (Some(_), None) => r -= 1,
+                _ => c -= 1,
+            }
+            cells.push((r, c));
+        }
+        cells.reverse();
+        Path { sum: best[rows * cols - 1], cells }
+    }
+
+    // The path with the smallest sum moving up, down, left or right, by
+    // Dijkstra's algorithm over the cells. Returns None if any cell is
+    // negative: the search relies on sums never shrinking along a path.
+    pub fn min_path_sum(&self) -> Option<Path> {
+        if self.cells.iter().any(|&v| v < 0) {
+            return None;
+        }
+        let (rows, cols) = (self.rows, self.cols);
+        let goal = rows * cols - 1;
+        let mut dist = vec![i128::MAX; rows * cols];
+        let mut prev = vec![usize::MAX; rows * cols];
+        let mut heap = BinaryHeap::new();
+        dist[0] = self.cells[0].into();
+        heap.push(Reverse((dist[0], 0)));
+
+        while let Some(Reverse((d, i))) = heap.pop() {
+            if d > dist[i] {
+                continue;
+            }
+            if i == goal {
+                break;
+            }
+            let (r, c) = (i / cols, i % cols);
+            let neighbours = [
+                (r > 0).then(|| i - cols),
+                (r + 1 < rows).then(|| i + cols),
+                (c > 0).then(|| i - 1),
+                (c + 1 < cols).then(|| i + 1),
+            ];
+            for j in neighbours.into_iter().flatten() {
+                let next = d + i128::from(self.cells[j]);
+                if next < dist[j] {
+                    dist[j] = next;
+                    prev[j] = i;
+                    heap.push(Reverse((next, j)));
+                }
+            }
+        }
+
+        let mut cells = vec![(goal / cols, goal % cols)];
+        let mut i = goal;
+        while i != 0 {
+            i = prev[i];
+            cells.push((i / cols, i % cols));
+        }
+        cells.reverse();
+        Some(Path { sum: dist[goal], cells })
+    }
+}
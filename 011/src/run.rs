@@ -0,0 +1,81 @@
+// A run of adjacent cells found by the product search, and an ASCII view
+// of the grid that marks it.
+
+use std::collections::HashSet;
+
+use crate::Grid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run<P = i64> {
+    pub product: P,
+    // (row, column) of the first cell
+    pub start: (usize, usize),
+    // (row step, column step) between consecutive cells
+    pub dir: (isize, isize),
+    // Cell values from the start onwards
+    pub values: Vec<i64>,
+    // Kept because wrapped runs cannot be recomputed from start and dir alone
+    cells: Vec<(usize, usize)>,
+}
+
+impl<P> Run<P> {
+    pub(crate) fn new(
+        product: P,
+        start: (usize, usize),
+        dir: (isize, isize),
+        values: Vec<i64>,
+        cells: Vec<(usize, usize)>,
+    ) -> Self {
+        Self { product, start, dir, values, cells }
+    }
+
+    // (row, column) of every cell in the run
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.cells.iter().copied()
+    }
+}
+
+impl Grid {
+    // The grid with the cells of `run` wrapped in brackets:
+    //
+    //    8  2 [22] 97
+    //   49 49  99 [40]
+    pub fn render<P>(&self, run: &Run<P>) -> String {
+        let marked: HashSet<(usize, usize)> = run.cells().collect();
+        let width = (0..self.rows())
+            .flat_map(|r| (0..self.cols()).map(move |c| (r, c)))
+            .map(|(r, c)| self.at(r, c).to_string().len())
+            .max()
+            .unwrap_or(0);
+
+        let mut out = String::new();
+        for r in 0..self.rows() {
+            let line: Vec<String> = (0..self.cols())
+                .map(|c| match marked.contains(&(r, c)) {
+                    true => format!("[{:>width$}]", self.at(r, c)),
+                    false => format!(" {:>width$} ", self.at(r, c)),
+                })
+                .collect();
+            out.push_str(line.join("").trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_marks_run() {
+        let grid = Grid::try_from_str("8 2 22 7\n49 49 99 40\n-1 5 6 7").unwrap();
+        let run = grid.max_run(2).unwrap().unwrap();
+        assert_eq!(run.product, 99 * 49);
+        assert_eq!(grid.render(&run), concat!(
+            "  8   2  22   7\n",
+            " 49 [49][99] 40\n",
+            " -1   5   6   7\n",
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+// Which runs the product search looks at: the step between consecutive
+// cells, and whether runs wrap around the grid's edges.
+
+#[cfg(test)]
+use std::ops::Range;
+
+use crate::Grid;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunSearch {
+    dirs: Vec<(isize, isize)>,
+    wrap: bool,
+}
+
+impl RunSearch {
+    // Any (row step, column step) other than (0, 0), e.g. (1, 2) for a
+    // knight-like step
+    pub fn new(dirs: &[(isize, isize)]) -> Self {
+        assert!(!dirs.contains(&(0, 0)), "direction (0, 0) never leaves its cell");
+        Self { dirs: dirs.to_vec(), wrap: false }
+    }
+
+    pub fn all_directions() -> Self {
+        Self::new(&Grid::ALL_DIRS)
+    }
+
+    pub fn knight() -> Self {
+        Self::new(&Grid::KNIGHT_DIRS)
+    }
+
+    // Treat the grid as a torus: a run leaving one edge comes back in on the
+    // opposite one. A run longer than its cycle visits some cells twice.
+    pub fn wrapping(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn dirs(&self) -> &[(isize, isize)] {
+        &self.dirs
+    }
+
+    pub fn wraps(&self) -> bool {
+        self.wrap
+    }
+}
+
+// Right, down and both diagonals, without wrapping
+impl Default for RunSearch {
+    fn default() -> Self {
+        Self::new(&Grid::DIRS)
+    }
+}
+
+// Starts along one axis of length n whose run of len cells, moving step at
+// a time, stays inside 0..n. Every start is valid on a torus. Only the
+// reference scan in the tests needs this now.
+#[cfg(test)]
+pub(crate) fn axis_starts(n: usize, step: isize, len: usize, wrap: bool) -> Range<usize> {
+    if wrap {
+        return 0..n;
+    }
+    let span = step.unsigned_abs() * (len - 1);
+    match span {
+        _ if span >= n => 0..0,
+        _ if step < 0 => span..n,
+        _ => 0..n - span,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn axis_starts_keep_runs_inside() {
+        for n in 1..8 {
+            for step in -3..=3 {
+                for len in 1..6 {
+                    let want: Vec<usize> = (0..n)
+                        .filter(|&s| (0..len).all(|k| (0..n as isize).contains(&(s as isize + step * k as isize))))
+                        .collect();
+                    let got: Vec<usize> = axis_starts(n, step, len, false).collect();
+                    assert_eq!(got, want, "n = {n}, step = {step}, len = {len}");
+                }
+            }
+        }
+        assert_eq!(axis_starts(3, 2, 10, true), 0..3);
+    }
+}
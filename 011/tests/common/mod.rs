@@ -0,0 +1,29 @@
+// Seeded test data shared by the integration tests. Not every test file
+// uses every helper.
+#![allow(dead_code)]
+
+use euler011::Grid;
+
+// Deterministic pseudo-random values in lo..=hi, from a 64-bit LCG
+pub fn sample_cells(n: usize, lo: i64, hi: i64, seed: u64) -> Vec<i64> {
+    let mut x = seed;
+    (0..n)
+        .map(|_| {
+            x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((x >> 33) % (hi - lo + 1) as u64) as i64 + lo
+        })
+        .collect()
+}
+
+// Whitespace-separated rows of `cols` cells
+pub fn grid_text(cells: &[i64], cols: usize) -> String {
+    let rows: Vec<String> = cells
+        .chunks(cols)
+        .map(|row| row.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" "))
+        .collect();
+    rows.join("\n")
+}
+
+pub fn sample_grid(rows: usize, cols: usize, lo: i64, hi: i64, seed: u64) -> Grid {
+    Grid::try_from_str(&grid_text(&sample_cells(rows * cols, lo, hi, seed), cols)).unwrap()
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use common::sample_grid;
+use euler011::{Grid, GridFormat, GridParseError, RunSearch};
+use num_bigint::BigInt;
+
+#[test]
+fn negative_numbers_pair_up() {
+    // Best run of 2 is -9 * -8 on the anti-diagonal
+    let grid = Grid::try_from_str("1 -9 2\n-8 3 1\n2 2 -1").unwrap();
+    assert_eq!(grid.max_product(2).unwrap(), 72);
+    let grid = Grid::try_from_str("-1 2\n3 4").unwrap();
+    assert_eq!(grid.max_product(2).unwrap(), 12);
+    let grid = Grid::try_from_str("-5").unwrap();
+    assert_eq!(grid.max_product(1).unwrap(), -5);
+}
+
+#[test]
+fn reads_every_format() {
+    let sources = [
+        ("1 2 3\n4 5 6\n7 8 9\n", GridFormat::Whitespace),
+        ("1,2,3\r\n4,5,6\r\n7,8,9\r\n", GridFormat::Csv),
+        ("[[1,2,3],[4,5,6],[7,8,9]]", GridFormat::Json),
+    ];
+    for (text, format) in sources {
+        let grid = Grid::read(text.as_bytes(), format).unwrap();
+        assert_eq!((grid.rows(), grid.cols()), (3, 3), "{format:?}");
+        assert_eq!(grid.max_product(3).unwrap(), 504, "{format:?}");
+    }
+}
+
+#[test]
+fn read_reports_invalid_utf8() {
+    let err = Grid::read(&[0xFF, 0xFE][..], GridFormat::Whitespace).unwrap_err();
+    assert!(matches!(err, GridParseError::Io(_)));
+}
+
+#[test]
+fn max_run_reports_position_and_direction() {
+    let grid = Grid::try_from_str("1 2 3 4\n5 9 7 8\n9 1 9 2\n3 4 5 9").unwrap();
+    let run = grid.max_run(3).unwrap().unwrap();
+    assert_eq!(run.product, 729);
+    assert_eq!((run.start, run.dir, run.values.clone()), ((1, 1), (1, 1), vec![9, 9, 9]));
+    assert_eq!(run.cells().collect::<Vec<_>>(), vec![(1, 1), (2, 2), (3, 3)]);
+    assert_eq!(grid.max_run(5), Ok(None));
+    assert_eq!(grid.max_product(5).unwrap(), 0);
+}
+
+#[test]
+fn top_runs_are_ordered() {
+    let grid = Grid::try_from_str("1 2 3 4\n5 9 7 8\n9 1 9 2\n3 4 5 9").unwrap();
+    let top = grid.top_runs(2, 4).unwrap();
+    let products: Vec<i64> = top.iter().map(|r| r.product).collect();
+    assert_eq!(products, vec![81, 81, 81, 72]);
+    // Ties keep scan order: by direction in Grid::DIRS, then by start
+    let order: Vec<_> = top[..3].iter().map(|r| (r.dir, r.start)).collect();
+    assert_eq!(order, vec![((1, 1), (1, 1)), ((1, 1), (2, 2)), ((1, -1), (1, 1))]);
+    assert_eq!(Some(&top[0]), grid.max_run(2).unwrap().as_ref());
+    // 4 directions x runs of 2: 12 + 12 + 9 + 9
+    assert_eq!(grid.top_runs(2, 100).unwrap().len(), 42);
+}
+
+// Best product over every start and direction, walking the cells directly
+fn brute_max(grid: &Grid, len: usize, search: &RunSearch) -> Option<i64> {
+    let (rows, cols) = (grid.rows() as isize, grid.cols() as isize);
+    let mut best = None;
+    for &(dr, dc) in search.dirs() {
+        for r in 0..rows {
+            for c in 0..cols {
+                let cells: Vec<(isize, isize)> = (0..len as isize).map(|k| (r + dr * k, c + dc * k)).collect();
+                let product = if search.wraps() {
+                    cells.iter().map(|&(r, c)| grid.at(r.rem_euclid(rows) as usize, c.rem_euclid(cols) as usize)).product()
+                } else if cells.iter().all(|&(r, c)| (0..rows).contains(&r) && (0..cols).contains(&c)) {
+                    cells.iter().map(|&(r, c)| grid.at(r as usize, c as usize)).product()
+                } else {
+                    continue;
+                };
+                best = best.max(Some(product));
+            }
+        }
+    }
+    best
+}
+
+#[test]
+fn direction_sets_match_brute_force() {
+    // Negatives and zeros
+    let grid = sample_grid(7, 5, -15, 25, 2024);
+    let searches = [
+        RunSearch::default(),
+        RunSearch::all_directions(),
+        RunSearch::knight(),
+        RunSearch::new(&[(0, 3), (-2, 1)]),
+    ];
+    for search in searches {
+        for wrap in [false, true] {
+            let search = search.clone().wrapping(wrap);
+            for len in 1..=8 {
+                let run = grid.max_run_with(len, &search).unwrap();
+                assert_eq!(run.as_ref().map(|r| r.product), brute_max(&grid, len, &search), "{search:?} len = {len}");
+                if let Some(run) = run {
+                    let values: Vec<i64> = run.cells().map(|(r, c)| grid.at(r, c)).collect();
+                    assert_eq!(values, run.values);
+                    assert_eq!(values.iter().product::<i64>(), run.product);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn wrapped_runs_cross_edges() {
+    let grid = Grid::try_from_str("9 1 1 8\n1 1 1 1\n1 1 1 1").unwrap();
+    assert_eq!(grid.max_product(2).unwrap(), 9);
+    let run = grid.max_run_with(2, &RunSearch::default().wrapping(true)).unwrap().unwrap();
+    assert_eq!(run.product, 72);
+    assert_eq!(run.cells().collect::<Vec<_>>(), vec![(0, 3), (0, 0)]);
+}
+
+#[test]
+fn runs_longer_than_one_side() {
+    // Rows are long enough for a run of 4 even though columns are not
+    let grid = Grid::try_from_str("1 2 3 4\n5 6 7 8").unwrap();
+    assert_eq!(grid.max_product(4).unwrap(), 5 * 6 * 7 * 8);
+}
+
+#[test]
+fn i64_search_reports_overflow() {
+    let grid = Grid::try_from_str(include_str!("../src/grid.txt")).unwrap();
+    let search = RunSearch::default();
+    for len in 9..=13 {
+        let exact = grid.max_run_exact(len, &search).unwrap();
+        match grid.max_product(len) {
+            Ok(product) => assert_eq!(BigInt::from(product), exact.product, "len = {len}"),
+            Err(e) => {
+                assert!(exact.product > BigInt::from(i64::MAX), "len = {len}");
+                assert_eq!(e.product, exact.product, "len = {len}");
+                assert_eq!(grid.top_runs(len, 3), Err(e));
+            }
+        }
+    }
+    assert!(grid.max_product(10).is_ok());
+    assert!(grid.max_product(11).is_err());
+}
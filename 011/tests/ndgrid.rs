@@ -0,0 +1,112 @@
+mod common;
+
+use common::{grid_text, sample_cells};
+use euler011::{Grid, NdGrid, NdRun, Run, RunSearch, ShapeError};
+
+// The same run with coordinate vectors
+fn as_nd(run: &Run) -> NdRun {
+    let (start, dir) = (vec![run.start.0, run.start.1], vec![run.dir.0, run.dir.1]);
+    NdRun { product: run.product, start, dir, values: run.values.clone() }
+}
+
+#[test]
+fn euler_grid_through_nd_grid() {
+    let grid = Grid::try_from_str(include_str!("../src/grid.txt")).unwrap();
+    let nd = NdGrid::from(&grid);
+    assert_eq!(nd.shape(), &[20, 20]);
+    assert_eq!(nd.max_product(4), grid.max_product(4));
+    assert_eq!(nd.max_product(4), Ok(70600674));
+    assert_eq!(nd.max_run(4).unwrap(), grid.max_run(4).unwrap().as_ref().map(as_nd));
+}
+
+#[test]
+fn two_dimensions_match_grid() {
+    for (rows, cols) in [(1, 1), (3, 5), (6, 4), (7, 7)] {
+        let cells = sample_cells(rows * cols, -8, 12, 11);
+        let grid = Grid::try_from_str(&grid_text(&cells, cols)).unwrap();
+        let nd = NdGrid::new(&[rows, cols], cells).unwrap();
+        assert_eq!(nd, NdGrid::from(&grid));
+        // Same runs in the same order, so ties break the same way
+        for len in 1..=8 {
+            let want: Vec<NdRun> = grid.top_runs(len, usize::MAX).unwrap().iter().map(as_nd).collect();
+            assert_eq!(nd.top_runs(len, usize::MAX).unwrap(), want, "{rows}x{cols} len = {len}");
+        }
+        let all = RunSearch::all_directions();
+        let dirs: Vec<Vec<isize>> = all.dirs().iter().map(|&(dr, dc)| vec![dr, dc]).collect();
+        for len in 1..=8 {
+            let want = grid.max_run_with(len, &all).unwrap().as_ref().map(as_nd);
+            assert_eq!(nd.max_run_with(len, &dirs).unwrap(), want, "{rows}x{cols} len = {len}");
+        }
+    }
+}
+
+// Best product over every start and direction, walking the cells directly
+fn brute_max(grid: &NdGrid, len: usize) -> Option<i64> {
+    let shape = grid.shape();
+    let mut best = None;
+    for dir in NdGrid::directions(grid.ndim()) {
+        let size: usize = shape.iter().product();
+        for index in 0..size {
+            let mut rest = index;
+            let mut start = vec![0; shape.len()];
+            for axis in (0..shape.len()).rev() {
+                start[axis] = rest % shape[axis];
+                rest /= shape[axis];
+            }
+            let cells: Option<Vec<Vec<usize>>> = (0..len as isize)
+                .map(|k| {
+                    start
+                        .iter()
+                        .zip(&dir)
+                        .zip(shape)
+                        .map(|((&x, &d), &n)| usize::try_from(x as isize + d * k).ok().filter(|&y| y < n))
+                        .collect()
+                })
+                .collect();
+            if let Some(cells) = cells {
+                best = best.max(Some(cells.iter().map(|c| grid.at(c)).product()));
+            }
+        }
+    }
+    best
+}
+
+#[test]
+fn volumes_match_brute_force() {
+    for shape in [vec![4, 3, 5], vec![2, 2, 2], vec![3, 3, 2, 3], vec![9]] {
+        let size = shape.iter().product();
+        let grid = NdGrid::new(&shape, sample_cells(size, -8, 12, 5)).unwrap();
+        for len in 1..=6 {
+            let run = grid.max_run(len).unwrap();
+            assert_eq!(run.as_ref().map(|r| r.product), brute_max(&grid, len), "{shape:?} len = {len}");
+            if let Some(run) = run {
+                let values: Vec<i64> = run.cells().map(|c| grid.at(&c)).collect();
+                assert_eq!(values, run.values);
+                assert_eq!(values.iter().product::<i64>(), run.product);
+            }
+        }
+    }
+}
+
+#[test]
+fn space_diagonal_run() {
+    // 2 * 3 * 4 along the main diagonal of a 3x3x3 cube of ones
+    let mut cells = vec![1; 27];
+    cells[0] = 2;
+    cells[13] = 3;
+    cells[26] = 4;
+    let grid = NdGrid::new(&[3, 3, 3], cells).unwrap();
+    let run = grid.max_run(3).unwrap().unwrap();
+    assert_eq!(run.product, 24);
+    assert_eq!((&run.start, &run.dir), (&vec![0, 0, 0], &vec![1, 1, 1]));
+    assert_eq!(run.cells().collect::<Vec<_>>(), vec![vec![0, 0, 0], vec![1, 1, 1], vec![2, 2, 2]]);
+}
+
+#[test]
+fn mismatched_shapes_are_errors() {
+    assert_eq!(NdGrid::new(&[], vec![1]), Err(ShapeError::NoAxes));
+    assert_eq!(NdGrid::new(&[2, 3], vec![0; 5]), Err(ShapeError::CellCount { shape: vec![2, 3], found: 5 }));
+    let err = NdGrid::new(&[usize::MAX, 2], vec![0; 4]).unwrap_err();
+    assert_eq!(err.to_string(), format!("4 cells do not fill shape [{}, 2]", usize::MAX));
+    assert!(NdGrid::new(&[0, 4], Vec::new()).is_ok());
+}
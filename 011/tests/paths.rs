@@ -0,0 +1,143 @@
+mod common;
+
+use common::sample_grid;
+use euler011::{Grid, Path};
+
+fn shapes() -> impl Iterator<Item = (usize, usize, u64)> {
+    [(1, 1), (1, 5), (4, 1), (3, 3), (3, 4), (4, 3)]
+        .into_iter()
+        .flat_map(|(rows, cols)| (1..=5).map(move |seed| (rows, cols, seed)))
+}
+
+fn rect_sum(grid: &Grid, (r0, c0): (usize, usize), (r1, c1): (usize, usize)) -> i128 {
+    (r0..=r1).flat_map(|r| (c0..=c1).map(move |c| i128::from(grid.at(r, c)))).sum()
+}
+
+fn path_sum(grid: &Grid, path: &Path) -> i128 {
+    path.cells.iter().map(|&(r, c)| i128::from(grid.at(r, c))).sum()
+}
+
+fn adjacent((r0, c0): (usize, usize), (r1, c1): (usize, usize)) -> bool {
+    r0.abs_diff(r1) + c0.abs_diff(c1) == 1
+}
+
+#[test]
+fn max_sum_rect_matches_brute_force() {
+    for (rows, cols, seed) in shapes() {
+        let grid = sample_grid(rows, cols, -9, 6, seed);
+        let mut best = i128::MIN;
+        for r0 in 0..rows {
+            for r1 in r0..rows {
+                for c0 in 0..cols {
+                    for c1 in c0..cols {
+                        best = best.max(rect_sum(&grid, (r0, c0), (r1, c1)));
+                    }
+                }
+            }
+        }
+        let rect = grid.max_sum_rect();
+        assert_eq!(rect.sum, best, "{rows}x{cols} seed {seed}");
+        assert_eq!(rect_sum(&grid, rect.top_left, rect.bottom_right), rect.sum);
+    }
+}
+
+#[test]
+fn max_sum_rect_of_negatives_is_one_cell() {
+    let grid = Grid::try_from_str("-3 -1\n-2 -5").unwrap();
+    let rect = grid.max_sum_rect();
+    assert_eq!((rect.sum, rect.top_left, rect.bottom_right), (-1, (0, 1), (0, 1)));
+}
+
+// Best sum over every right/down path from (r, c) to the corner
+fn brute_right_down(grid: &Grid, r: usize, c: usize) -> i128 {
+    let here = i128::from(grid.at(r, c));
+    let down = (r + 1 < grid.rows()).then(|| brute_right_down(grid, r + 1, c));
+    let right = (c + 1 < grid.cols()).then(|| brute_right_down(grid, r, c + 1));
+    here + down.max(right).unwrap_or(0)
+}
+
+#[test]
+fn sums_pass_i64_max() {
+    let grid = Grid::try_from_str("9223372036854775807 1\n1 1").unwrap();
+    let max = i128::from(i64::MAX);
+    let rect = grid.max_sum_rect();
+    assert_eq!((rect.sum, rect.top_left, rect.bottom_right), (max + 3, (0, 0), (1, 1)));
+    let path = grid.max_path_sum();
+    assert_eq!(path.sum, max + 2);
+    assert_eq!(path.sum, brute_right_down(&grid, 0, 0));
+    assert_eq!(grid.min_path_sum().unwrap().sum, max + 2);
+
+    let grid = Grid::try_from_str("-9223372036854775808 -9223372036854775808").unwrap();
+    assert_eq!(grid.max_path_sum().sum, 2 * i128::from(i64::MIN));
+    assert_eq!(grid.max_sum_rect().sum, i128::from(i64::MIN));
+}
+
+#[test]
+fn max_path_sum_matches_brute_force() {
+    for (rows, cols, seed) in shapes() {
+        let grid = sample_grid(rows, cols, -9, 9, seed);
+        let path = grid.max_path_sum();
+        assert_eq!(path.sum, brute_right_down(&grid, 0, 0), "{rows}x{cols} seed {seed}");
+        assert_eq!(path_sum(&grid, &path), path.sum);
+        assert_eq!(path.cells.len(), rows + cols - 1);
+        assert_eq!(path.cells.first(), Some(&(0, 0)));
+        for pair in path.cells.windows(2) {
+            let ((r0, c0), (r1, c1)) = (pair[0], pair[1]);
+            assert!((r1, c1) == (r0 + 1, c0) || (r1, c1) == (r0, c0 + 1));
+        }
+    }
+}
+
+// Smallest sum over every simple path from the top-left corner, by DFS
+fn brute_min_path(grid: &Grid, at: (usize, usize), seen: &mut Vec<(usize, usize)>, sum: i128, best: &mut i128) {
+    let sum = sum + i128::from(grid.at(at.0, at.1));
+    if at == (grid.rows() - 1, grid.cols() - 1) {
+        *best = (*best).min(sum);
+        return;
+    }
+    seen.push(at);
+    let (r, c) = at;
+    let steps = [(r.wrapping_sub(1), c), (r + 1, c), (r, c.wrapping_sub(1)), (r, c + 1)];
+    for next in steps {
+        if next.0 < grid.rows() && next.1 < grid.cols() && !seen.contains(&next) {
+            brute_min_path(grid, next, seen, sum, best);
+        }
+    }
+    seen.pop();
+}
+
+#[test]
+fn min_path_sum_matches_brute_force() {
+    for (rows, cols, seed) in shapes() {
+        let grid = sample_grid(rows, cols, 0, 20, seed);
+        let mut best = i128::MAX;
+        brute_min_path(&grid, (0, 0), &mut Vec::new(), 0, &mut best);
+        let path = grid.min_path_sum().unwrap();
+        assert_eq!(path.sum, best, "{rows}x{cols} seed {seed}");
+        assert_eq!(path_sum(&grid, &path), path.sum);
+        assert_eq!(path.cells.first(), Some(&(0, 0)));
+        assert_eq!(path.cells.last(), Some(&(rows - 1, cols - 1)));
+        assert!(path.cells.windows(2).all(|pair| adjacent(pair[0], pair[1])));
+    }
+}
+
+#[test]
+fn min_path_sum_goes_back_up() {
+    // The only route of ones snakes down the left, along the bottom, up the
+    // middle and down the right: 23 cells
+    let grid = Grid::try_from_str(
+        "1 1 1 99 1 1 1\n\
+         99 99 1 99 1 99 1\n\
+         1 1 1 99 1 99 1\n\
+         1 99 99 99 1 99 1\n\
+         1 1 1 1 1 99 1",
+    )
+    .unwrap();
+    let path = grid.min_path_sum().unwrap();
+    assert_eq!(path.sum, 23);
+    assert_eq!(path.cells.len(), 23);
+    assert!(path.cells.windows(2).any(|pair| pair[1].0 < pair[0].0));
+    assert!(path.cells.windows(2).any(|pair| pair[1].1 < pair[0].1));
+
+    assert!(Grid::try_from_str("1 -1\n1 1").unwrap().min_path_sum().is_none());
+}
@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use euler012::ArithSieve;
+
+fn bench_solve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve");
+    for target in [500, 1000] {
+        group.bench_with_input(BenchmarkId::new("trial", target), &target, |b, &target| {
+            b.iter(|| euler012::solve_trial(black_box(target)))
+        });
+        group.bench_with_input(BenchmarkId::new("linear_sieve", target), &target, |b, &target| {
+            b.iter(|| euler012::solve(black_box(target)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_tables(c: &mut Criterion) {
+    const LIMIT: usize = 1_000_000;
+    let mut group = c.benchmark_group("tables");
+    group.bench_function("sieve", |b| b.iter(|| ArithSieve::new(black_box(LIMIT))));
+    let sieve = ArithSieve::new(LIMIT);
+    group.bench_function("tau", |b| b.iter(|| sieve.divisor_counts()));
+    group.bench_function("sigma_1", |b| b.iter(|| sieve.divisor_sums(1)));
+    group.bench_function("phi", |b| b.iter(|| sieve.totients()));
+    group.finish();
+}
+
+fn criterion_config() -> Criterion {
+    Criterion::default()
+        .sample_size(50)
+        .warm_up_time(Duration::from_secs(2))
+        .measurement_time(Duration::from_secs(10))
+}
+
+criterion_group!(
+    name = divisors;
+    config = criterion_config();
+    targets = bench_solve, bench_tables
+);
+criterion_main!(divisors);
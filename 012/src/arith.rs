@@ -0,0 +1,179 @@
+// Arithmetic functions for every n up to a limit, from a linear sieve.
+//
+// The linear (Euler) sieve crosses each composite out exactly once, by its
+// smallest prime factor: m = i * p with p <= spf(i). Alongside spf(n) it
+// records the full power of that prime in n,
+//
+//   n = p^e * rest,  p = spf(n),  p does not divide rest
+//
+// so for any multiplicative f, f(n) = f(p^e) * f(rest) with rest < n. One
+// pass in increasing n then fills a whole table in O(N), and a single n is
+// factored in O(log n) by following spf.
+
+#[derive(Debug, Clone)]
+pub struct ArithSieve {
+    limit: usize,
+    // Smallest prime factor, 0 for n < 2
+    spf: Vec<u32>,
+    // spf(n)^e, the largest power of spf(n) dividing n
+    prime_power: Vec<u32>,
+    // e in the line above
+    exponent: Vec<u8>,
+    primes: Vec<u32>,
+}
+
+impl ArithSieve {
+    // Sieves 0..=limit
+    pub fn new(limit: usize) -> Self {
+        assert!(limit <= u32::MAX as usize, "limit {limit} does not fit the u32 tables");
+        let mut spf = vec![0_u32; limit + 1];
+        let mut prime_power = vec![0_u32; limit + 1];
+        let mut exponent = vec![0_u8; limit + 1];
+        let mut primes = Vec::new();
+
+        for i in 2..=limit {
+            if spf[i] == 0 {
+                spf[i] = i as u32;
+                prime_power[i] = i as u32;
+                exponent[i] = 1;
+                primes.push(i as u32);
+            }
+            for &p in &primes {
+                let m = i * p as usize;
+                // Invariant: p <= spf(i), so p is the smallest factor of m
+                if p > spf[i] || m > limit {
+                    break;
+                }
+                spf[m] = p;
+                if p == spf[i] {
+                    prime_power[m] = prime_power[i] * p;
+                    exponent[m] = exponent[i] + 1;
+                } else {
+                    prime_power[m] = p;
+                    exponent[m] = 1;
+                }
+            }
+        }
+        Self { limit, spf, prime_power, exponent, primes }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn primes(&self) -> &[u32] {
+        &self.primes
+    }
+
+    pub fn smallest_prime_factor(&self, n: usize) -> Option<u32> {
+        self.check(n);
+        (n >= 2).then(|| self.spf[n])
+    }
+
+    fn check(&self, n: usize) {
+        assert!(n <= self.limit, "{n} is past the sieve limit {}", self.limit);
+    }
+
+    // (prime, exponent) pairs of n in increasing order; empty for n < 2
+    pub fn factorize(&self, n: usize) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.check(n);
+        let mut rest = n;
+        std::iter::from_fn(move || {
+            if rest < 2 {
+                return None;
+            }
+            let factor = (self.spf[rest], self.exponent[rest] as u32);
+            rest /= self.prime_power[rest] as usize;
+            Some(factor)
+        })
+    }
+
+    // f(n) for every n <= limit, given f at prime powers as f(p, e).
+    // f(0) is left at T::default() and f(1) is one.
+    pub fn multiplicative<T>(&self, one: T, f: impl Fn(u64, u32) -> T) -> Vec<T>
+    where
+        T: Copy + Default + std::ops::Mul<Output = T>,
+    {
+        let mut table = vec![T::default(); self.limit + 1];
+        if self.limit >= 1 {
+            table[1] = one;
+        }
+        for n in 2..=self.limit {
+            let (p, e, power) = (self.spf[n], self.exponent[n], self.prime_power[n] as usize);
+            let rest = n / power;
+            // Invariant: rest < n, so its entry is already filled in
+            table[n] = if rest == 1 { f(p as u64, e as u32) } else { table[power] * table[rest] };
+        }
+        table
+    }
+
+    // Number of divisors, tau(n)
+    pub fn divisor_counts(&self) -> Vec<u32> {
+        self.multiplicative(1, |_, e| e + 1)
+    }
+
+    // Sum of the kth powers of the divisors, sigma_k(n). Panics if a value
+    // overflows u128.
+    pub fn divisor_sums(&self, k: u32) -> Vec<u128> {
+        self.multiplicative(1, |p, e| {
+            let pk = (p as u128).checked_pow(k).expect("sigma_k overflows u128");
+            (1..=e).fold((1_u128, 1_u128), |(sum, term), _| {
+                let term = term.checked_mul(pk).expect("sigma_k overflows u128");
+                (sum.checked_add(term).expect("sigma_k overflows u128"), term)
+            })
+            .0
+        })
+    }
+
+    // Euler's totient, phi(n)
+    pub fn totients(&self) -> Vec<u64> {
+        self.multiplicative(1, |p, e| (p - 1) * p.pow(e - 1))
+    }
+
+    // Moebius function, mu(n)
+    pub fn moebius(&self) -> Vec<i8> {
+        self.multiplicative(1, |_, e| if e == 1 { -1 } else { 0 })
+    }
+
+    // Number of prime factors with multiplicity, Omega(n). It is additive
+    // rather than multiplicative, but the same recurrence applies.
+    pub fn prime_factor_counts(&self) -> Vec<u8> {
+        let mut table = vec![0_u8; self.limit + 1];
+        for n in 2..=self.limit {
+            table[n] = self.exponent[n] + table[n / self.prime_power[n] as usize];
+        }
+        table
+    }
+
+    pub fn divisor_count(&self, n: usize) -> u32 {
+        self.factorize(n).map(|(_, e)| e + 1).product()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_tables() {
+        let sieve = ArithSieve::new(12);
+        assert_eq!(sieve.primes(), &[2, 3, 5, 7, 11]);
+        assert_eq!(sieve.divisor_counts(), vec![0, 1, 2, 2, 3, 2, 4, 2, 4, 3, 4, 2, 6]);
+        assert_eq!(sieve.divisor_sums(1), vec![0, 1, 3, 4, 7, 6, 12, 8, 15, 13, 18, 12, 28]);
+        assert_eq!(sieve.totients(), vec![0, 1, 1, 2, 2, 4, 2, 6, 4, 6, 4, 10, 4]);
+        assert_eq!(sieve.moebius(), vec![0, 1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0]);
+        assert_eq!(sieve.prime_factor_counts(), vec![0, 0, 1, 1, 2, 1, 2, 1, 3, 2, 2, 1, 3]);
+        assert_eq!(sieve.factorize(12).collect::<Vec<_>>(), vec![(2, 2), (3, 1)]);
+        assert_eq!(sieve.smallest_prime_factor(9), Some(3));
+        assert_eq!(sieve.smallest_prime_factor(1), None);
+    }
+
+    #[test]
+    fn tiny_limits() {
+        for limit in 0..3 {
+            let sieve = ArithSieve::new(limit);
+            assert_eq!(sieve.divisor_counts().len(), limit + 1);
+            assert_eq!(sieve.primes().len(), limit.saturating_sub(1));
+        }
+    }
+}
@@ -0,0 +1,53 @@
+mod arith;
+mod primes;
+
+pub use arith::ArithSieve;
+pub use primes::PrimeState;
+
+fn triangle_value(n: u64) -> u64 {
+    n * (n + 1) / 2
+}
+
+fn triangle_parts(n: u64) -> (u64, u64) {
+    if n % 2 == 0 { 
+        (n/2, n + 1)
+    } else {
+        (n, n.div_ceil(2))
+    }
+}
+
+// The original approach: factor both halves by trial division against a
+// growing prime list. Kept as the baseline for the benchmarks.
+pub fn solve_trial(target: u64) -> u64 {
+    let mut state = PrimeState::new();
+    let mut n: u64 = 1;
+    loop {
+        let (a, b) = triangle_parts(n);
+        let da = state.num_divisors(a);
+        let db = state.num_divisors(b);
+
+        if da * db > target {
+            return triangle_value(n);
+        }
+
+        n += 1;
+    }
+}
+
+// The halves of T(n) are coprime, so tau(T(n)) = tau(a) * tau(b), read
+// from a divisor-count table. The table doubles until it covers n + 1.
+pub fn solve(target: u64) -> u64 {
+    let mut limit = 1024;
+    let mut n: u64 = 1;
+    loop {
+        let tau = ArithSieve::new(limit).divisor_counts();
+        while n < limit as u64 {
+            let (a, b) = triangle_parts(n);
+            if tau[a as usize] as u64 * tau[b as usize] as u64 > target {
+                return triangle_value(n);
+            }
+            n += 1;
+        }
+        limit *= 2;
+    }
+}
@@ -0,0 +1,110 @@
+// A prime sieve that grows on demand, for trial division.
+//
+// Invariants, which the tests in tests/prime_state.rs check:
+//
+// - `sieve` has limit + 1 bits, and bit n is set iff n is prime.
+// - `primes` holds exactly the primes <= limit, in increasing order.
+//
+// Extending from old to new only sieves the new bits old+1..=new. Every
+// prime p with p * p <= new is either already known (p <= old) or is
+// itself in the new range and has been decided by the time the scan
+// reaches it, because its composite neighbours below p * p all have a
+// smaller factor.
+
+use bitvec::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct PrimeState {
+    limit: u64,
+    sieve: BitVec,
+    primes: Vec<u64>,
+}
+
+impl Default for PrimeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrimeState {
+    // Covers 0..=1, which holds no primes
+    pub fn new() -> Self {
+        Self { limit: 1, sieve: bitvec![0; 2], primes: Vec::new() }
+    }
+
+    // Every number up to and including the limit has been sieved
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    // The primes <= limit, in increasing order
+    pub fn primes(&self) -> &[u64] {
+        &self.primes
+    }
+
+    pub fn is_prime(&self, n: u64) -> bool {
+        assert!(n <= self.limit, "{n} is past the sieve limit {}", self.limit);
+        self.sieve[n as usize]
+    }
+
+    // Grows the sieve to cover `needed`, at least doubling it so repeated
+    // small extensions stay cheap overall
+    pub fn ensure_primes_up_to(&mut self, needed: u64) {
+        if needed <= self.limit {
+            return;
+        }
+        self.extend_to(needed.max(self.limit.saturating_mul(2)));
+    }
+
+    fn extend_to(&mut self, new_limit: u64) {
+        let old = self.limit as usize;
+        let new = usize::try_from(new_limit).expect("sieve limit does not fit in memory");
+
+        // New bits start as "maybe prime"; 0 and 1 are cleared in new()
+        self.sieve.resize(new + 1, true);
+
+        let mut p = 2;
+        while p * p <= new {
+            if self.sieve[p] {
+                // First multiple of p in the new range, skipping those
+                // already crossed out by smaller primes
+                let first = (p * p).max((old + 1).div_ceil(p) * p);
+                for m in (first..=new).step_by(p) {
+                    self.sieve.set(m, false);
+                }
+            }
+            p += 1;
+        }
+
+        self.primes.extend(self.sieve[old + 1..].iter_ones().map(|i| (old + 1 + i) as u64));
+        self.limit = new_limit;
+    }
+
+    // Number of divisors of n, by trial division up to its exact square
+    // root. Grows the sieve as needed.
+    pub fn num_divisors(&mut self, n: u64) -> u64 {
+        assert!(n >= 1, "0 has infinitely many divisors");
+        self.ensure_primes_up_to(n.isqrt());
+
+        let mut x = n;
+        let mut total = 1;
+        for &p in &self.primes {
+            // p * p > x, written so it cannot overflow
+            if p > x / p {
+                break;
+            }
+            let mut exp = 0;
+            while x % p == 0 {
+                x /= p;
+                exp += 1;
+            }
+            total *= exp + 1;
+        }
+        // What is left has no factor <= its square root, so it is 1 or prime
+        if x > 1 {
+            total * 2
+        } else {
+            total
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use euler012::{solve, solve_trial, ArithSieve};
+
+const LIMIT: usize = 3000;
+
+fn divisors(n: usize) -> Vec<u64> {
+    (1..=n as u64).filter(|d| n as u64 % d == 0).collect()
+}
+
+fn is_prime(n: u64) -> bool {
+    n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0)
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+#[test]
+fn tables_match_brute_force() {
+    let sieve = ArithSieve::new(LIMIT);
+    let (tau, phi, mu, omega) = (sieve.divisor_counts(), sieve.totients(), sieve.moebius(), sieve.prime_factor_counts());
+    let sigma: Vec<Vec<u128>> = (0..4).map(|k| sieve.divisor_sums(k)).collect();
+
+    for n in 1..=LIMIT {
+        let ds = divisors(n);
+        assert_eq!(tau[n] as usize, ds.len(), "tau({n})");
+        for (k, table) in sigma.iter().enumerate() {
+            let want: u128 = ds.iter().map(|&d| (d as u128).pow(k as u32)).sum();
+            assert_eq!(table[n], want, "sigma_{k}({n})");
+        }
+        let coprime = (1..=n as u64).filter(|&m| gcd(m, n as u64) == 1).count();
+        assert_eq!(phi[n] as usize, coprime, "phi({n})");
+
+        let factors: Vec<(u32, u32)> = sieve.factorize(n).collect();
+        let product: u64 = factors.iter().map(|&(p, e)| (p as u64).pow(e)).product();
+        assert_eq!(product, n as u64);
+        assert!(factors.iter().all(|&(p, _)| is_prime(p as u64)));
+        assert!(factors.windows(2).all(|w| w[0].0 < w[1].0));
+
+        let big_omega: u32 = factors.iter().map(|&(_, e)| e).sum();
+        assert_eq!(omega[n] as u32, big_omega, "Omega({n})");
+        let want_mu = match factors.iter().all(|&(_, e)| e == 1) {
+            true if factors.len() % 2 == 0 => 1,
+            true => -1,
+            false => 0,
+        };
+        assert_eq!(mu[n], want_mu, "mu({n})");
+        assert_eq!(sieve.divisor_count(n), tau[n]);
+    }
+
+    let primes: Vec<u32> = (0..=LIMIT as u32).filter(|&n| is_prime(n as u64)).collect();
+    assert_eq!(sieve.primes(), &primes[..]);
+}
+
+#[test]
+fn solve_matches_trial_division() {
+    assert_eq!(solve(5), 28);
+    for target in (0..=300).step_by(7) {
+        assert_eq!(solve(target), solve_trial(target), "target = {target}");
+    }
+    assert_eq!(solve(500), 76576500);
+}
@@ -0,0 +1,81 @@
+use euler012::PrimeState;
+use proptest::prelude::*;
+
+fn is_prime(n: u64) -> bool {
+    n >= 2 && (2..n).take_while(|d| d * d <= n).all(|d| n % d != 0)
+}
+
+fn fresh(limit: u64) -> PrimeState {
+    let mut state = PrimeState::new();
+    state.ensure_primes_up_to(limit);
+    state
+}
+
+fn brute_divisors(n: u64) -> u64 {
+    (1..=n)
+        .take_while(|d| d * d <= n)
+        .map(|d| match n % d {
+            0 if d * d == n => 1,
+            0 => 2,
+            _ => 0,
+        })
+        .sum()
+}
+
+#[test]
+fn starts_empty() {
+    let state = PrimeState::new();
+    assert_eq!(state.limit(), 1);
+    assert!(state.primes().is_empty());
+    assert!(!state.is_prime(0) && !state.is_prime(1));
+}
+
+#[test]
+fn ensure_grows_by_doubling() {
+    let mut state = fresh(10);
+    assert_eq!(state.limit(), 10);
+    assert_eq!(state.primes(), &[2, 3, 5, 7]);
+    // A small step still doubles the limit; a covered one does nothing
+    state.ensure_primes_up_to(11);
+    assert_eq!(state.limit(), 20);
+    state.ensure_primes_up_to(15);
+    assert_eq!(state.limit(), 20);
+    assert_eq!(state.primes(), &[2, 3, 5, 7, 11, 13, 17, 19]);
+}
+
+#[test]
+fn divisors_of_large_primes_and_squares() {
+    // The square root is exact, so p itself is sieved for p * p
+    let mut state = PrimeState::new();
+    for p in [2_u64, 3, 65_521, 999_983] {
+        assert_eq!(state.num_divisors(p * p), 3, "p = {p}");
+    }
+    assert_eq!(state.num_divisors(4_294_967_291), 2);
+    assert_eq!(state.num_divisors(999_983 * 999_979), 4);
+    assert_eq!(state.num_divisors(1), 1);
+}
+
+proptest! {
+    #[test]
+    fn any_growth_matches_a_fresh_sieve(steps in prop::collection::vec(0_u64..5_000, 1..12)) {
+        let mut state = PrimeState::new();
+        for &needed in &steps {
+            state.ensure_primes_up_to(needed);
+            prop_assert!(state.limit() >= needed);
+            let want = fresh(state.limit());
+            prop_assert_eq!(state.primes(), want.primes());
+        }
+        let primes: Vec<u64> = (0..=state.limit()).filter(|&n| is_prime(n)).collect();
+        prop_assert_eq!(state.primes(), &primes[..]);
+        for n in 0..=state.limit() {
+            prop_assert_eq!(state.is_prime(n), is_prime(n));
+        }
+    }
+
+    #[test]
+    fn num_divisors_matches_brute_force(n in 1_u64..200_000, warm in 0_u64..1_000) {
+        // The answer must not depend on how far the sieve already reaches
+        let mut state = fresh(warm);
+        prop_assert_eq!(state.num_divisors(n), brute_divisors(n));
+    }
+}